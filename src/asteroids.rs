@@ -0,0 +1,402 @@
+use nalgebra_glm as glm;
+use raylib::prelude::Color;
+use std::collections::HashMap;
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::Vec3;
+use crate::mesh::Mesh;
+use crate::noise::{Fractal, Noise, fbm};
+use crate::obj::Face;
+use crate::render::NO_OUTLINE;
+use crate::shaders::{Fragment, SunLight, smoothstep};
+
+// Cinturón de asteroides: miles de rocas, cada una en su órbita y girando sobre un
+// eje propio. Todas comparten unas pocas mallas deformadas; según el tamaño en
+// pantalla se dibuja la malla detallada, la simple o un único píxel.
+
+// Tope de rocas de un cinturón (cada una se simula y se prueba contra la cámara)
+pub const MAX_ROCKS: usize = 50_000;
+// Formas distintas de roca (cada una con dos niveles de detalle)
+const ROCK_VARIANTS: u32 = 8;
+// Subdivisiones del icosaedro base: 1280 caras de cerca, 80 de lejos
+const DETAIL_SUBDIVISIONS: usize = 3;
+const SIMPLE_SUBDIVISIONS: usize = 1;
+// Radio en píxeles por debajo del cual la roca es un punto / usa la malla simple
+const POINT_MAX_PX: f32 = 1.5;
+const SIMPLE_MAX_PX: f32 = 10.0;
+// Alfa mínima de un punto: de lejos el cinturón sigue leyéndose como una banda
+const POINT_MIN_COVERAGE: f32 = 0.3;
+// Luz mínima de la cara no iluminada (las rocas son oscuras; sin esto desaparecen)
+const ROCK_AMBIENT: f32 = 0.08;
+// Margen de colisión de la cámara sobre el radio de la roca (como los planetas)
+const COLLISION_FACTOR: f32 = 1.2;
+
+// Definición del cinturón (sección [belt] de la escena). Radios en unidades de
+// `Scene::orbit_base`; tamaños en radios de la esfera base.
+#[derive(Copy, Clone)]
+pub struct AsteroidBelt {
+    pub inner: f32,
+    pub outer: f32,
+    pub count: usize,
+    pub seed: u32,
+    // inclinación máxima de las órbitas (rad): grosor del cinturón
+    pub thickness: f32,
+    pub size_min: f32,
+    pub size_max: f32,
+    // velocidad angular en el borde interior (rad/s); hacia fuera cae como r^-1.5
+    pub orbit_speed: f32,
+    pub color: Vec3,
+}
+
+impl Default for AsteroidBelt {
+    fn default() -> Self {
+        Self {
+            inner: 3.45,
+            outer: 3.95,
+            count: 2500,
+            seed: 11,
+            thickness: 0.06,
+            size_min: 0.012,
+            size_max: 0.09,
+            orbit_speed: 0.21,
+            color: glm::vec3(0.52, 0.47, 0.42),
+        }
+    }
+}
+
+// Elementos fijos de una roca
+struct Rock {
+    orbit: f32,
+    phase: f32,
+    speed: f32,
+    // plano de la órbita: inclinación alrededor de la línea de nodos
+    inclination: f32,
+    node: Vec3,
+    size: f32,
+    spin_axis: Vec3,
+    spin_speed: f32,
+    variant: usize,
+    // variación de albedo entre rocas
+    tint: f32,
+}
+
+// Posición y orientación de una roca en el frame actual
+#[derive(Copy, Clone)]
+struct RockPose {
+    center: Vec3,
+    radius: f32,
+    spin: f32,
+}
+
+// Cinturón listo para simular y dibujar; se reconstruye al recargar la escena
+pub struct AsteroidField {
+    // [detallada, simple] por variante
+    meshes: Vec<[Mesh; 2]>,
+    rocks: Vec<Rock>,
+    poses: Vec<RockPose>,
+    color: Vec3,
+    grain: Noise,
+}
+
+// Roca cercana que se dibuja como malla
+pub struct RockMesh<'a> {
+    pub mesh: &'a Mesh,
+    pub model: glm::Mat4,
+    pub center: Vec3,
+    pub outline: i32,
+    color: Vec3,
+    grain: Noise,
+}
+
+// Roca lejana: un píxel con la luz de toda la roca y cobertura como alfa
+struct RockPoint {
+    x: i32,
+    y: i32,
+    z: f32,
+    color: Color,
+}
+
+// Resultado del recorte por frame: mallas (van con la geometría opaca) y puntos
+// (se mezclan después del cielo, ver draw_points)
+pub struct VisibleRocks<'a> {
+    pub meshes: Vec<RockMesh<'a>>,
+    points: Vec<RockPoint>,
+}
+
+impl AsteroidField {
+    pub fn new(belt: &AsteroidBelt) -> Self {
+        let noise = Noise::new(belt.seed);
+        let meshes = (0..ROCK_VARIANTS)
+            .map(|v| {
+                let shape = noise.derive(100 + v);
+                [DETAIL_SUBDIVISIONS, SIMPLE_SUBDIVISIONS].map(|n| rock_mesh(&shape, n))
+            })
+            .collect();
+
+        let random = noise.derive(1);
+        let rocks = (0..belt.count as u32)
+            .map(|i| {
+                let r = |k: u32| random.random(i * 16 + k);
+                // más densidad hacia el centro del cinturón (suma de dos uniformes)
+                let orbit = belt.inner + (belt.outer - belt.inner) * (r(0) + r(1)) * 0.5;
+                let node_angle = r(3) * std::f32::consts::TAU;
+                // muchas rocas pequeñas y pocas grandes
+                let size = belt.size_min * (belt.size_max / belt.size_min).powf(r(5).powi(3));
+                Rock {
+                    orbit,
+                    phase: r(2) * std::f32::consts::TAU,
+                    speed: belt.orbit_speed * (belt.inner / orbit).powf(1.5),
+                    inclination: (r(4) * 2.0 - 1.0) * belt.thickness,
+                    node: glm::vec3(node_angle.cos(), 0.0, node_angle.sin()),
+                    size,
+                    spin_axis: random_direction(r(6), r(7)),
+                    spin_speed: 0.3 + 1.7 * r(8),
+                    variant: (r(9) * ROCK_VARIANTS as f32) as usize % ROCK_VARIANTS as usize,
+                    tint: 0.7 + 0.45 * r(10),
+                }
+            })
+            .collect();
+
+        Self {
+            meshes,
+            rocks,
+            poses: Vec::new(),
+            color: belt.color,
+            grain: noise.derive(2),
+        }
+    }
+
+    // Avanza las órbitas al instante `time`; `orbit_base` y `sphere_radius` en
+    // unidades de mundo
+    pub fn update(&mut self, time: f32, orbit_base: f32, sphere_radius: f32) {
+        self.poses.clear();
+        self.poses.extend(self.rocks.iter().map(|rock| {
+            let angle = rock.phase + rock.speed * time;
+            let radius = orbit_base * rock.orbit;
+            let flat = glm::vec3(radius * angle.cos(), 0.0, radius * angle.sin());
+            RockPose {
+                center: glm::rotate_vec3(&flat, rock.inclination, &rock.node),
+                radius: sphere_radius * rock.size,
+                spin: rock.spin_speed * time,
+            }
+        }));
+    }
+
+    // Esferas de colisión de la cámara (centro, radio)
+    pub fn collision_spheres(&self) -> impl Iterator<Item = (Vec3, f32)> + '_ {
+        self.poses
+            .iter()
+            .map(|pose| (pose.center, pose.radius * COLLISION_FACTOR))
+    }
+
+    // Recorta contra la vista y elige el nivel de detalle por el radio en píxeles
+    pub fn visible(
+        &self,
+        view: &glm::Mat4,
+        proj: &glm::Mat4,
+        fb: &FrameBuffer,
+        sun_pos: Vec3,
+    ) -> VisibleRocks<'_> {
+        let (w, h) = (fb.width as f32, fb.height as f32);
+        let view_proj = proj * view;
+        let cam_pos = glm::inverse(view).column(3).xyz();
+        let px_per_unit = proj[(1, 1)] * h * 0.5;
+        let mut visible = VisibleRocks {
+            meshes: Vec::new(),
+            points: Vec::new(),
+        };
+
+        for (rock, pose) in self.rocks.iter().zip(&self.poses) {
+            let clip = view_proj * glm::vec4(pose.center.x, pose.center.y, pose.center.z, 1.0);
+            if clip.w <= pose.radius {
+                continue;
+            }
+            let radius_px = pose.radius * px_per_unit / clip.w;
+            let sx = (clip.x / clip.w * 0.5 + 0.5) * w;
+            let sy = (1.0 - (clip.y / clip.w * 0.5 + 0.5)) * h;
+            if sx < -radius_px || sy < -radius_px || sx > w + radius_px || sy > h + radius_px {
+                continue;
+            }
+            let color = self.color * rock.tint;
+
+            if radius_px < POINT_MAX_PX {
+                // fase: fracción del disco iluminada vista desde la cámara
+                let to_sun = glm::normalize(&(sun_pos - pose.center));
+                let to_cam = glm::normalize(&(cam_pos - pose.center));
+                let phase = 0.5 * (1.0 + glm::dot(&to_sun, &to_cam));
+                let light = ROCK_AMBIENT + (1.0 - ROCK_AMBIENT) * phase;
+                // área del disco en píxeles como cobertura (se desvanece al alejarse)
+                let area = std::f32::consts::PI * radius_px * radius_px;
+                let coverage = area.clamp(POINT_MIN_COVERAGE, 1.0);
+                let channel = |c: f32| ((c * light).min(1.0) * 255.0) as u8;
+                visible.points.push(RockPoint {
+                    x: sx.floor() as i32,
+                    y: sy.floor() as i32,
+                    z: clip.z / clip.w,
+                    color: Color::new(
+                        channel(color.x),
+                        channel(color.y),
+                        channel(color.z),
+                        (coverage * 255.0) as u8,
+                    ),
+                });
+                continue;
+            }
+
+            let (lod, outline) = if radius_px < SIMPLE_MAX_PX {
+                (1, NO_OUTLINE)
+            } else {
+                (0, 1)
+            };
+            let model = glm::translation(&pose.center)
+                * glm::rotation(pose.spin, &rock.spin_axis)
+                * glm::scaling(&glm::vec3(pose.radius, pose.radius, pose.radius));
+            visible.meshes.push(RockMesh {
+                mesh: &self.meshes[rock.variant][lod],
+                model,
+                center: pose.center,
+                outline,
+                color,
+                grain: self.grain,
+            });
+        }
+        visible
+    }
+}
+
+impl RockMesh<'_> {
+    // Lambert con un mínimo de ambiente y algo de grano (espacio modelo)
+    pub fn shade(&self, frag: &Fragment, sun: &SunLight) -> Color {
+        let ndl = glm::dot(&frag.n, &sun.dir).max(0.0) * sun.visibility;
+        let grain = 0.85 + 0.3 * self.grain.value(frag.p * 6.0);
+        let c = self.color * (grain * (ROCK_AMBIENT + (1.0 - ROCK_AMBIENT) * ndl));
+        let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0) as u8;
+        Color::new(channel(c.x), channel(c.y), channel(c.z), 255)
+    }
+}
+
+impl VisibleRocks<'_> {
+    // Rocas lejanas: mezcla con prueba de profundidad y sin escribirla. Va después
+    // del cielo (que solo pinta donde no hay geometría) para no quedar debajo.
+    pub fn draw_points(&self, fb: &mut FrameBuffer) {
+        fb.set_blend(true);
+        for p in &self.points {
+            fb.set_color(p.color);
+            fb.set_pixel_z(p.x, p.y, p.z);
+        }
+        fb.set_blend(false);
+    }
+}
+
+// Dirección uniforme en la esfera a partir de dos números en [0, 1)
+fn random_direction(u: f32, v: f32) -> Vec3 {
+    let y = u * 2.0 - 1.0;
+    let ring = (1.0 - y * y).sqrt();
+    let angle = v * std::f32::consts::TAU;
+    glm::vec3(ring * angle.cos(), y, ring * angle.sin())
+}
+
+//
+// ----- MALLAS DE ROCA -----
+//
+
+// Icosaedro subdividido y deformado: bultos de fBm, cráteres de Worley y un
+// estiramiento por ejes. Los mismos parámetros en los dos niveles de detalle dan
+// la misma silueta. Radio envolvente 1.
+fn rock_mesh(shape: &Noise, subdivisions: usize) -> Mesh {
+    let (directions, triangles) = icosphere(subdivisions);
+    let stretch = glm::vec3(
+        1.0,
+        0.6 + 0.3 * shape.random(0),
+        0.45 + 0.35 * shape.random(1),
+    );
+    let craters = shape.derive(1);
+    let mut vertices: Vec<Vec3> = directions
+        .iter()
+        .map(|&d| {
+            let lumps = fbm(d * 1.4, &Fractal::octaves(4), |q| shape.simplex(q));
+            let (f1, _) = craters.worley(d * 2.5);
+            let dent = 1.0 - smoothstep(0.0, 0.35, f1);
+            let r = 1.0 + 0.25 * lumps - 0.12 * dent;
+            d.component_mul(&stretch) * r
+        })
+        .collect();
+    let extent = vertices.iter().map(|v| v.magnitude()).fold(0.0, f32::max);
+    for v in &mut vertices {
+        *v /= extent;
+    }
+
+    let faces = triangles
+        .into_iter()
+        .map(|vertex_indices| Face {
+            vertex_indices,
+            normal_indices: None,
+        })
+        .collect();
+    Mesh::from_parts(vertices, &[], faces)
+}
+
+// Esfera unitaria: icosaedro con cada triángulo partido en cuatro `subdivisions`
+// veces (caras en sentido antihorario vistas desde fuera)
+fn icosphere(subdivisions: usize) -> (Vec<Vec3>, Vec<[usize; 3]>) {
+    let t = (1.0 + 5.0f32.sqrt()) * 0.5;
+    let mut vertices: Vec<Vec3> = [
+        (-1.0, t, 0.0),
+        (1.0, t, 0.0),
+        (-1.0, -t, 0.0),
+        (1.0, -t, 0.0),
+        (0.0, -1.0, t),
+        (0.0, 1.0, t),
+        (0.0, -1.0, -t),
+        (0.0, 1.0, -t),
+        (t, 0.0, -1.0),
+        (t, 0.0, 1.0),
+        (-t, 0.0, -1.0),
+        (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|&(x, y, z)| glm::normalize(&glm::vec3(x, y, z)))
+    .collect();
+    let mut faces = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // punto medio de cada arista, compartido por las dos caras que la usan
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize| {
+            let key = (a.min(b), a.max(b));
+            *midpoints.entry(key).or_insert_with(|| {
+                vertices.push(glm::normalize(&(vertices[a] + vertices[b])));
+                vertices.len() - 1
+            })
+        };
+        faces = faces
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+    (vertices, faces)
+}
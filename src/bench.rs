@@ -0,0 +1,175 @@
+use nalgebra_glm as glm;
+use raylib::prelude::*;
+use std::time::Instant;
+
+use spacetravel::camera::Camera;
+use spacetravel::framebuffer::FrameBuffer;
+use spacetravel::geom::Vec3;
+use spacetravel::mesh::Mesh;
+use spacetravel::shaders::{BodyShader, Fragment, ShaderParams, SunLight, shade_body};
+use spacetravel::transform::project_vertices_perspective;
+use spacetravel::triangle::triangle_filled;
+
+const BENCH_WIDTH: i32 = 1000;
+const BENCH_HEIGHT: i32 = 700;
+
+//
+// ----- RUTA ANTERIOR: Image::draw_pixel POR FRAGMENTO (solo referencia) -----
+//
+
+fn legacy_triangle_filled(
+    img: &mut Image,
+    z_buffer: &mut [f32],
+    color: Color,
+    a: &Vec3,
+    b: &Vec3,
+    c: &Vec3,
+) {
+    let (w, h) = (img.width, img.height);
+    let min_x = a.x.min(b.x).min(c.x).floor().max(0.0) as i32;
+    let max_x = a.x.max(b.x).max(c.x).ceil().min((w - 1) as f32) as i32;
+    let min_y = a.y.min(b.y).min(c.y).floor().max(0.0) as i32;
+    let max_y = a.y.max(b.y).max(c.y).ceil().min((h - 1) as f32) as i32;
+
+    let den = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
+    if den.abs() < 1e-6 {
+        return;
+    }
+
+    let eps = 1e-6;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let px = x as f32 + 0.5;
+            let py = y as f32 + 0.5;
+            let w0 = ((b.y - c.y) * (px - c.x) + (c.x - b.x) * (py - c.y)) / den;
+            let w1 = ((c.y - a.y) * (px - c.x) + (a.x - c.x) * (py - c.y)) / den;
+            let w2 = 1.0 - w0 - w1;
+            if w0 >= -eps && w1 >= -eps && w2 >= -eps {
+                let z = w0 * a.z + w1 * b.z + w2 * c.z;
+                let i = (y * w + x) as usize;
+                if z < z_buffer[i] {
+                    z_buffer[i] = z;
+                    img.draw_pixel(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+//
+// ----- BENCHMARK: SOL A PANTALLA COMPLETA -----
+//
+
+// Rasteriza el Sol llenando toda la pantalla `frames` veces con la ruta de
+// slices del FrameBuffer y con la ruta anterior (Image::draw_pixel), e imprime
+// el tiempo medio por frame de cada una. No abre ventana.
+pub fn run_sun_bench(sphere_path: &str, frames: usize) -> anyhow::Result<()> {
+    let frames = frames.max(1);
+    let mesh = Mesh::load(sphere_path)?;
+    let (vertices, faces) = (&mesh.vertices, &mesh.faces);
+    let center = mesh.center;
+    let radius = mesh.bounding_radius();
+
+    // Cámara lo bastante cerca para que el disco cubra también las esquinas
+    let cam = Camera {
+        pos: glm::vec3(center.x, center.y, center.z + radius * 1.3),
+        ..Camera::default()
+    };
+    let aspect = BENCH_WIDTH as f32 / BENCH_HEIGHT as f32;
+    let mvp = cam.proj_matrix(aspect) * cam.view_matrix();
+    let mv = cam.view_matrix();
+
+    let screen_vertices = project_vertices_perspective(vertices, &mvp, BENCH_WIDTH, BENCH_HEIGHT);
+    let view_vertices: Vec<glm::Vec3> = vertices
+        .iter()
+        .map(|v| {
+            let p = mv * glm::vec4(v.x, v.y, v.z, 1.0);
+            glm::vec3(p.x, p.y, p.z)
+        })
+        .collect();
+
+    // El sombreado por cara es igual en ambas rutas; se calcula por frame.
+    // El Sol es emisivo: la luz que recibe no influye.
+    let sun = SunLight {
+        dir: glm::vec3(0.0, 1.0, 0.0),
+        visibility: 1.0,
+    };
+    let params = ShaderParams::default();
+    let shade_faces = |time: f32| -> Vec<Color> {
+        faces
+            .iter()
+            .zip(&mesh.face_normals)
+            .map(|(f, n)| {
+                let [i0, i1, i2] = f.vertex_indices;
+                let (va, vb, vc) = (view_vertices[i0], view_vertices[i1], view_vertices[i2]);
+                let frag = Fragment {
+                    p: (vertices[i0] + vertices[i1] + vertices[i2]) / 3.0,
+                    n: *n,
+                    n_view: glm::normalize(&glm::cross(&(vb - va), &(vc - va))),
+                };
+                shade_body(
+                    &BodyShader::Star,
+                    &params,
+                    &frag,
+                    center,
+                    radius,
+                    time,
+                    &sun,
+                )
+            })
+            .collect()
+    };
+
+    println!(
+        "Benchmark Sol a pantalla completa: {}x{}, {} caras, {} frames",
+        BENCH_WIDTH,
+        BENCH_HEIGHT,
+        faces.len(),
+        frames
+    );
+
+    // Ruta actual: slices + tramos por fila
+    let mut fb = FrameBuffer::new(BENCH_WIDTH, BENCH_HEIGHT, Color::BLACK);
+    let start = Instant::now();
+    for frame in 0..frames {
+        let colors = shade_faces(frame as f32 / 60.0);
+        fb.clear();
+        for (f, color) in faces.iter().zip(colors) {
+            let [i0, i1, i2] = f.vertex_indices;
+            fb.set_color(color);
+            triangle_filled(
+                &mut fb,
+                &screen_vertices[i0],
+                &screen_vertices[i1],
+                &screen_vertices[i2],
+            );
+        }
+    }
+    let slice_ms = start.elapsed().as_secs_f64() * 1000.0 / frames as f64;
+
+    // Ruta anterior: Image nueva por frame + draw_pixel por fragmento
+    let mut z_buffer = vec![f32::INFINITY; (BENCH_WIDTH * BENCH_HEIGHT) as usize];
+    let start = Instant::now();
+    for frame in 0..frames {
+        let colors = shade_faces(frame as f32 / 60.0);
+        let mut img = Image::gen_image_color(BENCH_WIDTH, BENCH_HEIGHT, Color::BLACK);
+        z_buffer.fill(f32::INFINITY);
+        for (f, color) in faces.iter().zip(colors) {
+            let [i0, i1, i2] = f.vertex_indices;
+            legacy_triangle_filled(
+                &mut img,
+                &mut z_buffer,
+                color,
+                &screen_vertices[i0],
+                &screen_vertices[i1],
+                &screen_vertices[i2],
+            );
+        }
+    }
+    let legacy_ms = start.elapsed().as_secs_f64() * 1000.0 / frames as f64;
+
+    println!("  Image::draw_pixel : {legacy_ms:8.3} ms/frame");
+    println!("  slices FrameBuffer: {slice_ms:8.3} ms/frame");
+    println!("  mejora            : {:8.2}x", legacy_ms / slice_ms.max(1e-9));
+    Ok(())
+}
@@ -1,41 +1,113 @@
 use raylib::prelude::*;
 
+use crate::geom::Vec3;
+use crate::profiler::RenderStats;
+
 pub struct FrameBuffer {
     pub width: i32,
     pub height: i32,
-    pub color_buffer: Image,
+    // RGBA8 contiguo, fila por fila; solo se convierte a Image al exportar
+    color_buffer: Vec<[u8; 4]>,
     background_color: Color,
-    current_color: Color,
+    current_color: [u8; 4],
+    // false = solo se escribe profundidad (p. ej. relleno de hidden-line)
+    color_write: bool,
+    // true = mezcla alfa sobre lo existente sin escribir profundidad (cascos translúcidos)
+    blend: bool,
     z_buffer: Vec<f32>, // <-- NUEVO
+    pub stats: RenderStats,
+}
+
+#[inline]
+fn rgba(color: Color) -> [u8; 4] {
+    [color.r, color.g, color.b, color.a]
+}
+
+// src sobre dst con el alfa de src (el destino queda opaco)
+#[inline]
+fn blend_over(dst: &mut [u8; 4], src: [u8; 4]) {
+    let a = src[3] as u32;
+    for (d, s) in dst.iter_mut().zip(src).take(3) {
+        *d = ((s as u32 * a + *d as u32 * (255 - a) + 127) / 255) as u8;
+    }
 }
 
 impl FrameBuffer {
     pub fn new(width: i32, height: i32, background_color: Color) -> Self {
-        let color_buffer = Image::gen_image_color(width, height, background_color);
+        let len = (width * height) as usize;
         FrameBuffer {
             width,
             height,
-            color_buffer,
+            color_buffer: vec![rgba(background_color); len],
             background_color,
-            current_color: Color::WHITE,
-            z_buffer: vec![f32::INFINITY; (width * height) as usize], // <-- NUEVO
+            current_color: rgba(Color::WHITE),
+            color_write: true,
+            blend: false,
+            z_buffer: vec![f32::INFINITY; len], // <-- NUEVO
+            stats: RenderStats::default(),
         }
     }
 
     pub fn set_background_color(&mut self, color: Color) {
         self.background_color = color;
-        self.color_buffer = Image::gen_image_color(self.width, self.height, color);
-        self.z_buffer.fill(f32::INFINITY);
+        self.clear();
     }
 
+    // Reasigna color y z-buffer cuando cambia la resolución interna
+    pub fn resize(&mut self, width: i32, height: i32) {
+        let width = width.max(1);
+        let height = height.max(1);
+        if width == self.width && height == self.height {
+            return;
+        }
+        let len = (width * height) as usize;
+        self.width = width;
+        self.height = height;
+        self.color_buffer = vec![rgba(self.background_color); len];
+        self.z_buffer = vec![f32::INFINITY; len];
+    }
+
+    // Limpia en sitio: no reasigna memoria en cada frame
     pub fn clear(&mut self) {
-        self.color_buffer = Image::gen_image_color(self.width, self.height, self.background_color);
+        self.color_buffer.fill(rgba(self.background_color));
         self.z_buffer.fill(f32::INFINITY); // <-- NUEVO
+        self.stats = RenderStats::default();
+    }
+
+    // Solo profundidad (mapas de sombra: el color no se usa)
+    pub fn clear_depth(&mut self) {
+        self.z_buffer.fill(f32::INFINITY);
+        self.stats = RenderStats::default();
+    }
+
+    // Profundidad guardada en (x, y); INFINITY si nada se rasterizó ahí
+    #[inline]
+    pub fn depth_at(&self, x: i32, y: i32) -> f32 {
+        if self.in_bounds(x, y) {
+            self.z_buffer[self.idx(x, y)]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    // Bytes RGBA8 del color buffer, listos para subir a una textura
+    pub fn pixels(&self) -> &[u8] {
+        self.color_buffer.as_flattened()
     }
 
     #[inline]
     pub fn set_color(&mut self, color: Color) {
-        self.current_color = color;
+        self.current_color = rgba(color);
+    }
+
+    #[inline]
+    pub fn set_color_write(&mut self, enabled: bool) {
+        self.color_write = enabled;
+    }
+
+    #[inline]
+    pub fn set_blend(&mut self, enabled: bool) {
+        self.blend = enabled;
     }
 
     #[inline]
@@ -50,7 +122,8 @@ impl FrameBuffer {
     #[inline]
     pub fn set_pixel(&mut self, x: i32, y: i32) {
         if self.in_bounds(x, y) {
-            self.color_buffer.draw_pixel(x, y, self.current_color);
+            let i = self.idx(x, y);
+            self.color_buffer[i] = self.current_color;
         }
     }
 
@@ -60,14 +133,129 @@ impl FrameBuffer {
         if self.in_bounds(x, y) {
             let i = self.idx(x, y);
             if z < self.z_buffer[i] {
-                self.z_buffer[i] = z;
-                self.color_buffer.draw_pixel(x, y, self.current_color);
+                if self.blend {
+                    blend_over(&mut self.color_buffer[i], self.current_color);
+                } else {
+                    self.z_buffer[i] = z;
+                    if self.color_write {
+                        self.color_buffer[i] = self.current_color;
+                    }
+                }
+                self.stats.pixels_shaded += 1;
+            } else {
+                self.stats.depth_rejected += 1;
             }
         }
     }
 
+    // Suma luz (rgb en 0..1) al píxel, saturando; sin profundidad (postprocesos)
+    #[inline]
+    pub fn add_pixel(&mut self, x: i32, y: i32, rgb: Vec3) {
+        if self.in_bounds(x, y) {
+            let i = self.idx(x, y);
+            let px = &mut self.color_buffer[i];
+            for (c, add) in px.iter_mut().zip([rgb.x, rgb.y, rgb.z]) {
+                *c = (*c as f32 + add * 255.0).clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    // Fila completa (color + profundidad) para escribir sin pasar por set_pixel
+    #[inline]
+    pub fn row_mut(&mut self, y: i32) -> (&mut [[u8; 4]], &mut [f32]) {
+        let w = self.width as usize;
+        let start = y as usize * w;
+        (
+            &mut self.color_buffer[start..start + w],
+            &mut self.z_buffer[start..start + w],
+        )
+    }
+
+    // Tramo horizontal [x0, x1] con z lineal (z0 en x0, +dz por píxel) y prueba de profundidad.
+    // El llamador garantiza que el tramo está dentro del framebuffer.
+    #[inline]
+    pub fn fill_span_z(&mut self, y: i32, x0: i32, x1: i32, z0: f32, dz: f32) {
+        let color = self.current_color;
+        let color_write = self.color_write;
+        let blend = self.blend;
+        let (row_color, row_z) = self.row_mut(y);
+        let (x0, x1) = (x0 as usize, x1 as usize);
+        let mut z = z0;
+        let mut written = 0;
+        for (c, depth) in row_color[x0..=x1].iter_mut().zip(&mut row_z[x0..=x1]) {
+            if z < *depth {
+                if blend {
+                    blend_over(c, color);
+                } else {
+                    *depth = z;
+                    if color_write {
+                        *c = color;
+                    }
+                }
+                written += 1;
+            }
+            z += dz;
+        }
+        self.stats.pixels_shaded += written;
+        self.stats.depth_rejected += (x1 - x0 + 1) as u64 - written;
+    }
+
+    // Igual que fill_span_z pero el color sale de `shade(x)`, que solo se evalúa
+    // para los píxeles que pasan la prueba de profundidad
+    #[inline]
+    pub fn fill_span_z_shaded(
+        &mut self,
+        y: i32,
+        x0: i32,
+        x1: i32,
+        z0: f32,
+        dz: f32,
+        mut shade: impl FnMut(i32) -> Color,
+    ) {
+        let color_write = self.color_write;
+        let blend = self.blend;
+        let (row_color, row_z) = self.row_mut(y);
+        let mut z = z0;
+        let mut written = 0;
+        for x in x0..=x1 {
+            let i = x as usize;
+            if z < row_z[i] {
+                if blend {
+                    blend_over(&mut row_color[i], rgba(shade(x)));
+                } else {
+                    row_z[i] = z;
+                    if color_write {
+                        row_color[i] = rgba(shade(x));
+                    }
+                }
+                written += 1;
+            }
+            z += dz;
+        }
+        self.stats.pixels_shaded += written;
+        self.stats.depth_rejected += (x1 - x0 + 1) as u64 - written;
+    }
+
+    // Copia el buffer a una Image de raylib (para crear texturas o exportar)
+    pub fn to_image(&self) -> Image {
+        let image = Image::gen_image_color(self.width, self.height, self.background_color);
+        let bytes = self.pixels();
+        // gen_image_color crea imágenes R8G8B8A8 de width*height, igual que nuestro buffer
+        assert_eq!(image.get_pixel_data_size(), bytes.len());
+        // SAFETY: `image.data` es el bloque de píxeles que reservó gen_image_color y
+        // mide get_pixel_data_size() bytes, igual a bytes.len() (comprobado arriba).
+        // Los bytes no piden alineación ([u8; 4] tampoco). La imagen es nuestra y
+        // nadie más ve el bloque mientras vive la rebanada, que no sale de este
+        // bloque; `bytes` es memoria del framebuffer, así que no se solapan.
+        unsafe {
+            std::slice::from_raw_parts_mut(image.data as *mut u8, bytes.len())
+                .copy_from_slice(bytes);
+        }
+        image
+    }
+
     pub fn render_to_file(&self, file_path: &str) -> anyhow::Result<()> {
-        self.color_buffer.export_image(file_path);
+        self.to_image().export_image(file_path);
         Ok(())
     }
 }
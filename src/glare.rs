@@ -0,0 +1,287 @@
+use nalgebra_glm as glm;
+use raylib::prelude::Color;
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::{Vec2, Vec3};
+use crate::noise::{Fractal, Noise, ridged};
+use crate::params::params_struct;
+use crate::shaders::smoothstep;
+
+// Resplandor del Sol: corona como billboard emisivo (con prueba de profundidad)
+// y destello de lente en espacio de pantalla que se apaga cuando algo tapa el Sol.
+
+// Muestras angulares de los filamentos de la corona (se calculan una vez por frame)
+const STREAMER_SAMPLES: usize = 256;
+// Rejilla de muestras del disco solar para la oclusión del destello
+const OCCLUSION_GRID: i32 = 7;
+
+//
+// ----- SOL EN PANTALLA -----
+//
+
+// Posición (px) y radio aparente (px) del Sol, más la profundidad NDC de su centro
+// (plano del billboard) y de su punto más cercano a la cámara
+pub struct SunOnScreen {
+    pub pos: Vec2,
+    pub radius: f32,
+    z_center: f32,
+    z_front: f32,
+}
+
+impl SunOnScreen {
+    // None si el Sol queda detrás de la cámara
+    pub fn project(
+        center: Vec3,
+        radius: f32,
+        cam_pos: Vec3,
+        cam_right: Vec3,
+        view_proj: &glm::Mat4,
+        fb: &FrameBuffer,
+    ) -> Option<Self> {
+        let (w, h) = (fb.width as f32, fb.height as f32);
+        let to_ndc = |p: Vec3| {
+            let clip = view_proj * glm::vec4(p.x, p.y, p.z, 1.0);
+            (clip.w > 1e-4).then(|| glm::vec3(clip.x, clip.y, clip.z) / clip.w)
+        };
+        let to_px = |ndc: Vec3| glm::vec2((ndc.x * 0.5 + 0.5) * w, (1.0 - (ndc.y * 0.5 + 0.5)) * h);
+
+        let c = to_ndc(center)?;
+        let front = to_ndc(center + glm::normalize(&(cam_pos - center)) * radius)?;
+        let edge = to_ndc(center + cam_right * radius)?;
+        let pos = to_px(c);
+        Some(Self {
+            pos,
+            radius: glm::distance(&pos, &to_px(edge)).max(0.5),
+            z_center: c.z,
+            z_front: front.z,
+        })
+    }
+
+    // Fracción visible del disco (0 = tapado o fuera de pantalla). Compara la
+    // profundidad ya rasterizada con la del punto más cercano del Sol: la esfera
+    // facetada queda por dentro, así que solo lo que está delante la tapa.
+    pub fn visibility(&self, fb: &FrameBuffer) -> f32 {
+        let mut visible = 0;
+        let mut total = 0;
+        let half = OCCLUSION_GRID / 2;
+        for gy in -half..=half {
+            for gx in -half..=half {
+                let (u, v) = (gx as f32 / half as f32, gy as f32 / half as f32);
+                if u * u + v * v > 1.0 {
+                    continue;
+                }
+                total += 1;
+                let x = (self.pos.x + u * self.radius * 0.9).floor() as i32;
+                let y = (self.pos.y + v * self.radius * 0.9).floor() as i32;
+                let inside = x >= 0 && y >= 0 && x < fb.width && y < fb.height;
+                if inside && fb.depth_at(x, y) >= self.z_front - 1e-5 {
+                    visible += 1;
+                }
+            }
+        }
+        visible as f32 / total as f32
+    }
+
+    // Fuerza del destello (0..1): disco visible y cámara mirando hacia el Sol;
+    // `facing` = coseno entre la dirección de la cámara y la del Sol
+    pub fn flare_strength(&self, fb: &FrameBuffer, facing: f32) -> f32 {
+        self.visibility(fb) * smoothstep(0.6, 1.0, facing)
+    }
+}
+
+//
+// ----- CORONA -----
+//
+
+params_struct! {
+    // Corona: halo y filamentos animados alrededor del disco (radios en radios del Sol)
+    pub struct CoronaParams {
+        inner_color: glm::Vec3 = glm::vec3(1.00, 0.86, 0.55),
+        outer_color: glm::Vec3 = glm::vec3(1.00, 0.42, 0.10),
+        // radio exterior del billboard
+        size: f32 = 3.2,
+        intensity: f32 = 0.9,
+        // caída exponencial del halo por radio solar
+        falloff: f32 = 3.0,
+        // número aproximado de filamentos y su brillo
+        streamers: f32 = 6.0,
+        streamer_strength: f32 = 0.8,
+        streamer_speed: f32 = 0.06,
+    }
+}
+
+// Billboard del tamaño de la corona en el plano del centro del Sol, siempre de
+// frente a la cámara: el disco opaco lo tapa y los cuerpos delante también.
+// Mezcla alfa sin escribir profundidad (después de la geometría opaca).
+pub fn draw_corona(fb: &mut FrameBuffer, sun: &SunOnScreen, p: &CoronaParams, time: f32) {
+    if !(-1.0..=1.0).contains(&sun.z_center) {
+        return;
+    }
+    let extent = sun.radius * p.size;
+    let x0 = ((sun.pos.x - extent).floor() as i32).max(0);
+    let x1 = ((sun.pos.x + extent).ceil() as i32).min(fb.width - 1);
+    let y0 = ((sun.pos.y - extent).floor() as i32).max(0);
+    let y1 = ((sun.pos.y + extent).ceil() as i32).min(fb.height - 1);
+    if x0 > x1 || y0 > y1 {
+        return;
+    }
+
+    // Filamentos: crestas de ruido sobre el ángulo que cambian lentamente
+    let noise = Noise::new(2718);
+    let streamers: Vec<f32> = (0..STREAMER_SAMPLES)
+        .map(|i| {
+            let a = i as f32 / STREAMER_SAMPLES as f32 * std::f32::consts::TAU;
+            let q = glm::vec3(a.cos(), a.sin(), time * p.streamer_speed) * p.streamers;
+            ridged(q, &Fractal::octaves(3), |x| noise.simplex(x)).powi(2)
+        })
+        .collect();
+
+    let span = (p.size - 1.0).max(1e-3);
+    fb.set_blend(true);
+    for y in y0..=y1 {
+        let dy = y as f32 + 0.5 - sun.pos.y;
+        fb.fill_span_z_shaded(y, x0, x1, sun.z_center, 0.0, |x| {
+            let dx = x as f32 + 0.5 - sun.pos.x;
+            let r = dx.hypot(dy) / sun.radius;
+            if r >= p.size {
+                return Color::new(0, 0, 0, 0);
+            }
+            let h = (r - 1.0).max(0.0);
+            let angle = dy.atan2(dx).rem_euclid(std::f32::consts::TAU);
+            let slot = (angle / std::f32::consts::TAU * STREAMER_SAMPLES as f32) as usize;
+            let streamer = streamers[slot.min(STREAMER_SAMPLES - 1)];
+
+            // halo que cae rápido + filamentos que llegan más lejos
+            let glow = (-h * p.falloff).exp();
+            let rays = streamer * p.streamer_strength * (-h * p.falloff * 0.35).exp();
+            let fade = 1.0 - smoothstep(p.size * 0.6, p.size, r);
+            let alpha = ((glow + rays) * p.intensity * fade).clamp(0.0, 1.0);
+
+            let t = (h / span).clamp(0.0, 1.0);
+            let col = p.inner_color * (1.0 - t) + p.outer_color * t;
+            let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0) as u8;
+            Color::new(
+                channel(col.x),
+                channel(col.y),
+                channel(col.z),
+                (alpha * 255.0) as u8,
+            )
+        });
+    }
+    fb.set_blend(false);
+}
+
+//
+// ----- DESTELLO DE LENTE -----
+//
+
+params_struct! {
+    // Destello en pantalla: estrella de rayos sobre el Sol, reflejos (ghosts) sobre
+    // la recta Sol-centro y un velo suave. Tamaños relativos al alto de la imagen.
+    pub struct FlareParams {
+        intensity: f32 = 1.0,
+        starburst_color: glm::Vec3 = glm::vec3(1.00, 0.90, 0.70),
+        starburst_size: f32 = 0.35,
+        // número de rayos (pares)
+        rays: f32 = 8.0,
+        ghost_color: glm::Vec3 = glm::vec3(0.55, 0.75, 1.00),
+        ghost_intensity: f32 = 0.18,
+        veil: f32 = 0.03,
+    }
+}
+
+// Reflejos internos: posición sobre la recta (0 = Sol, 1 = centro de pantalla),
+// radio relativo al alto de la imagen y brillo
+const GHOSTS: [(f32, f32, f32); 6] = [
+    (0.45, 0.020, 1.0),
+    (0.75, 0.045, 0.6),
+    (1.15, 0.030, 0.8),
+    (1.40, 0.080, 0.35),
+    (1.75, 0.025, 0.9),
+    (2.10, 0.120, 0.25),
+];
+
+// `strength` (0..1) combina la visibilidad del disco y cuánto mira la cámara al Sol
+pub fn draw_lens_flare(fb: &mut FrameBuffer, sun: &SunOnScreen, strength: f32, p: &FlareParams) {
+    let strength = strength * p.intensity;
+    if strength < 0.01 {
+        return;
+    }
+    let h = fb.height as f32;
+
+    // Velo: toda la imagen se aclara un poco hacia el color del Sol
+    let veil = p.starburst_color * (p.veil * strength);
+    for y in 0..fb.height {
+        for x in 0..fb.width {
+            fb.add_pixel(x, y, veil);
+        }
+    }
+
+    // Estrella de rayos: |cos(n·θ/2)| afilado da n rayos
+    let length = h * p.starburst_size * strength;
+    let half_rays = p.rays * 0.5;
+    for_each_in_disc(fb, sun.pos, length, |fb, x, y, dx, dy, d| {
+        let angle = dy.atan2(dx);
+        let rays = (angle * half_rays).cos().abs().powi(48)
+            + (angle * half_rays + std::f32::consts::FRAC_PI_2 / half_rays)
+                .cos()
+                .abs()
+                .powi(96)
+                * 0.5;
+        let radial = (1.0 - d / length).powi(2);
+        let core = (-(d / (sun.radius * 1.5)).powi(2)).exp() * 0.6;
+        fb.add_pixel(
+            x,
+            y,
+            p.starburst_color * ((rays + core) * radial * strength),
+        );
+    });
+
+    // Reflejos sobre la recta que une el Sol con el centro de la pantalla
+    let center = glm::vec2(fb.width as f32 * 0.5, h * 0.5);
+    let axis = center - sun.pos;
+    for (i, &(t, size, brightness)) in GHOSTS.iter().enumerate() {
+        let pos = sun.pos + axis * t;
+        let radius = h * size;
+        // tono algo distinto en cada reflejo (aberración de la lente)
+        let hue = 0.8 + 0.4 * (i as f32 * 1.7).sin().abs();
+        let color = glm::vec3(
+            p.ghost_color.x * hue,
+            p.ghost_color.y,
+            p.ghost_color.z / hue,
+        ) * (p.ghost_intensity * brightness * strength);
+        for_each_in_disc(fb, pos, radius, |fb, x, y, _, _, d| {
+            // disco con borde algo más brillante
+            let r = d / radius;
+            let k = smoothstep(1.0, 0.85, r) * (0.6 + 0.4 * r * r);
+            fb.add_pixel(x, y, color * k);
+        });
+    }
+}
+
+// Recorre los píxeles del framebuffer dentro del disco (centro, radio en px);
+// `f` recibe el píxel, su desplazamiento al centro y la distancia
+fn for_each_in_disc(
+    fb: &mut FrameBuffer,
+    center: Vec2,
+    radius: f32,
+    mut f: impl FnMut(&mut FrameBuffer, i32, i32, f32, f32, f32),
+) {
+    if radius < 0.5 {
+        return;
+    }
+    let x0 = ((center.x - radius).floor() as i32).max(0);
+    let x1 = ((center.x + radius).ceil() as i32).min(fb.width - 1);
+    let y0 = ((center.y - radius).floor() as i32).max(0);
+    let y1 = ((center.y + radius).ceil() as i32).min(fb.height - 1);
+    for y in y0..=y1 {
+        let dy = y as f32 + 0.5 - center.y;
+        for x in x0..=x1 {
+            let dx = x as f32 + 0.5 - center.x;
+            let d = dx.hypot(dy);
+            if d < radius {
+                f(fb, x, y, dx, dy, d);
+            }
+        }
+    }
+}
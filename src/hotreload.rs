@@ -0,0 +1,196 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Result;
+
+// Cada cuánto se consulta la fecha de modificación de los archivos vigilados
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Vigila la fecha de modificación de un archivo (sin hilos: se consulta por frame)
+pub struct FileWatcher {
+    path: String,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            modified: modified_time(path),
+            last_poll: Instant::now(),
+        }
+    }
+
+    // true si el archivo cambió (o apareció/desapareció) desde la última consulta
+    pub fn poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Valor leído de un archivo que se recarga al cambiar. Si la recarga falla se
+// conserva el último valor válido y se guarda el error para mostrarlo.
+pub struct Reloadable<T> {
+    pub value: T,
+    pub error: Option<String>,
+    // el primero es el archivo que se pasa a `load`; el resto, archivos que `load`
+    // lee por su cuenta y que también provocan la recarga
+    watchers: Vec<FileWatcher>,
+    // índice del archivo que provocó la última recarga
+    changed: usize,
+}
+
+impl<T> Reloadable<T> {
+    // Carga inicial; si falla se arranca con `fallback` y el error pendiente
+    pub fn new(path: &str, load: impl Fn(&str) -> Result<T>, fallback: impl FnOnce() -> T) -> Self {
+        let watchers = vec![FileWatcher::new(path)];
+        match load(path) {
+            Ok(value) => Self {
+                value,
+                error: None,
+                watchers,
+                changed: 0,
+            },
+            Err(e) => Self {
+                value: fallback(),
+                error: Some(format!("{e:#}")),
+                watchers,
+                changed: 0,
+            },
+        }
+    }
+
+    // Vigila además `path` (un archivo auxiliar que lee `load`)
+    pub fn also_watching(mut self, path: &str) -> Self {
+        self.watchers.push(FileWatcher::new(path));
+        self
+    }
+
+    // Recarga si alguno de los archivos cambió; devuelve true si `value` se reemplazó
+    pub fn update(&mut self, load: impl Fn(&str) -> Result<T>) -> bool {
+        // se consultan todos para que cada uno registre su fecha
+        let mut changed = None;
+        for (i, watcher) in self.watchers.iter_mut().enumerate() {
+            if watcher.poll() {
+                changed.get_or_insert(i);
+            }
+        }
+        let Some(changed) = changed else {
+            return false;
+        };
+        self.changed = changed;
+        match load(&self.watchers[0].path) {
+            Ok(value) => {
+                self.value = value;
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                self.error = Some(format!("{e:#}"));
+                false
+            }
+        }
+    }
+
+    // Archivo que provocó la última recarga (al principio, el principal)
+    pub fn path(&self) -> &str {
+        &self.watchers[self.changed].path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // Archivo temporal propio de cada prueba
+    fn temp_file(name: &str, text: &str) -> String {
+        let path = std::env::temp_dir().join(format!("spacetravel-{}-{name}", std::process::id()));
+        fs::write(&path, text).unwrap();
+        path.display().to_string()
+    }
+
+    // Reescribe el archivo con una fecha de modificación `secs` segundos adelante
+    // (sin depender de la resolución del sistema de archivos) y espera a que toque
+    // volver a consultarla
+    fn rewrite(path: &str, text: &str, secs: u64) {
+        fs::write(path, text).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(secs))
+            .unwrap();
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    fn load_number(path: &str) -> Result<f32> {
+        let text = fs::read_to_string(path)?;
+        text.trim()
+            .parse()
+            .map_err(|e| anyhow::anyhow!("{path}: {e}"))
+    }
+
+    #[test]
+    fn failed_reload_keeps_last_value() {
+        let path = temp_file("value.txt", "1.5");
+        let mut number = Reloadable::new(&path, load_number, || 0.0);
+        assert_eq!((number.value, number.error.as_deref()), (1.5, None));
+        assert!(!number.update(load_number));
+
+        rewrite(&path, "1,5", 10);
+        assert!(!number.update(load_number));
+        assert_eq!(number.value, 1.5);
+        let error = number.error.clone().unwrap_or_default();
+        assert!(error.starts_with(&path), "{error}");
+
+        // sin cambios en el archivo el error se sigue mostrando
+        std::thread::sleep(POLL_INTERVAL);
+        assert!(!number.update(load_number));
+        assert!(number.error.is_some());
+
+        rewrite(&path, "2.5", 20);
+        assert!(number.update(load_number));
+        assert_eq!((number.value, number.error.as_deref()), (2.5, None));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_first_load_uses_fallback() {
+        let path = temp_file("broken.txt", "x");
+        let number = Reloadable::new(&path, load_number, || 7.0);
+        assert_eq!(number.value, 7.0);
+        assert!(number.error.is_some());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn auxiliary_file_triggers_reload() {
+        let path = temp_file("main.txt", "1");
+        let extra = temp_file("extra.txt", "");
+        let mut number = Reloadable::new(&path, load_number, || 0.0).also_watching(&extra);
+        assert_eq!(number.path(), path);
+
+        // el principal cambia sin que cambie su fecha: solo el auxiliar avisa
+        let modified = modified_time(&path).unwrap();
+        fs::write(&path, "3").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(modified).unwrap();
+        rewrite(&extra, "", 10);
+        assert!(number.update(load_number));
+        assert_eq!(number.value, 3.0);
+        assert_eq!(number.path(), extra);
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&extra).unwrap();
+    }
+}
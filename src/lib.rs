@@ -0,0 +1,27 @@
+// Rasterizador por software y shaders del sistema solar. El ejecutable (main.rs)
+// arma la escena y la ventana; otros crates pueden usar el render y registrar
+// sus propios shaders de superficie (ver registry::ShaderRegistry).
+
+pub mod asteroids;
+pub mod camera;
+pub mod framebuffer;
+pub mod geom;
+pub mod glare;
+pub mod line;
+pub mod mesh;
+pub mod noise;
+pub mod obj;
+pub mod params;
+pub mod pixel;
+pub mod profiler;
+pub mod registry;
+pub mod rings;
+pub mod render;
+pub mod scene;
+pub mod setup;
+pub mod shaders;
+pub mod shadow;
+pub mod sky;
+pub mod starfield;
+pub mod transform;
+pub mod triangle;
@@ -30,9 +30,9 @@ pub fn line(fb: &mut FrameBuffer, start: &Vec3, end: &Vec3) {
     }
 }
 
-// Línea con grosor N (1..3 recomendado) usando z-buffer
+// Línea con grosor N (1..3 recomendado; 0 = un solo píxel) usando z-buffer
 pub fn line_depth_thick(fb: &mut FrameBuffer, a: &Vec3, b: &Vec3, thickness: i32) {
-    let t = thickness.max(1);
+    let t = thickness.max(0);
     let (x1, y1, z1) = (a.x, a.y, a.z);
     let (x2, y2, z2) = (b.x, b.y, b.z);
     let dx = x2 - x1;
@@ -1,46 +1,60 @@
-mod camera;
-mod framebuffer;
-mod geom;
-mod line;
-mod obj;
-mod pixel;
-mod setup;
-mod shaders;
-mod transform;
-mod triangle;
+mod bench;
+mod hotreload;
 
 use nalgebra_glm as glm;
 use raylib::prelude::*;
-use std::collections::HashMap;
 
-use crate::camera::Camera;
-use crate::framebuffer::FrameBuffer;
-use crate::geom::Vec3;
-use crate::line::line_depth_thick;
-use crate::obj::load_obj;
-use crate::shaders::{BodyShader, shade_body};
-use crate::transform::project_vertices_perspective;
-use crate::triangle::triangle_filled;
-
-const WIDTH: i32 = 1000;
-const HEIGHT: i32 = 700;
-
-const STAR_COUNT: usize = 400;
-
-struct PlanetDef {
-    kind: BodyShader,
-    orbit_radius: f32,
-    orbit_speed: f32,
-    self_speed: f32,
-    scale: f32,
-    has_moon: bool,
-}
-
-struct Instance {
+use spacetravel::asteroids::AsteroidField;
+use spacetravel::camera::Camera;
+use spacetravel::framebuffer::FrameBuffer;
+use spacetravel::geom::Vec3;
+use spacetravel::glare::{SunOnScreen, draw_corona, draw_lens_flare};
+use spacetravel::line::line_depth_thick;
+use spacetravel::mesh::Mesh;
+use spacetravel::profiler::Profiler;
+use spacetravel::registry::ShaderRegistry;
+use spacetravel::render::{
+    NO_OUTLINE, RenderMode, RenderModes, ShadingMode, Surface, draw_mesh, draw_shell,
+    project_mesh,
+};
+use spacetravel::rings::{RingHalf, RingInstance};
+use spacetravel::scene::{SCENE_PATH, Scene};
+use spacetravel::shaders::{
+    BodyShader, SHADER_PARAMS_PATH, ShaderParams, SunLight, shade_body, shade_shell,
+    sun_direction_model,
+};
+use spacetravel::shadow::{SHADOW_MAP_SIZE, ShadowMap};
+use spacetravel::sky::SkyBox;
+use spacetravel::starfield::{STAR_CATALOG_PATH, Starfield};
+
+use crate::hotreload::Reloadable;
+
+// Tamaño inicial de la ventana; luego se sigue el tamaño real en cada frame
+const INITIAL_WIDTH: i32 = 1000;
+const INITIAL_HEIGHT: i32 = 700;
+
+// Escalas de render internas (1.0 = resolución de la ventana)
+const RENDER_SCALES: [f32; 4] = [1.0, 0.75, 0.5, 0.25];
+
+// Semilla del planeta procedural exterior (se cambia con --seed)
+const DEFAULT_PLANET_SEED: u32 = 2024;
+
+// Segundos que se muestra el aviso de recarga de un archivo
+const RELOAD_NOTICE_SECS: f32 = 2.0;
+
+const SHIP_LABEL: &str = "Nave";
+const BELT_LABEL: &str = "Asteroides";
+// Luz mínima de la nave en las caras que no miran al Sol
+const SHIP_AMBIENT: f32 = 0.55;
+
+struct Instance<'a> {
     kind: BodyShader,
+    // índice del cuerpo en `render_targets` (su modo de render)
+    target: usize,
     model: glm::Mat4,
     center_world: glm::Vec3,
     radius_collision: f32,
+    rings: Option<RingInstance<'a>>,
 }
 
 struct WarpState {
@@ -56,8 +70,8 @@ struct WarpState {
 
 fn draw_orbit(fb: &mut FrameBuffer, view: &glm::Mat4, proj: &glm::Mat4, radius: f32, color: Color) {
     let segments = 128;
-    let w = WIDTH as f32;
-    let h = HEIGHT as f32;
+    let w = fb.width as f32;
+    let h = fb.height as f32;
     let mvp = proj * view;
 
     // z fijo MUY LEJOS para que siempre quede detrás de todo en el z-buffer
@@ -123,85 +137,12 @@ fn draw_orbit(fb: &mut FrameBuffer, view: &glm::Mat4, proj: &glm::Mat4, radius:
     }
 }
 
-//
-// ----- JÚPITER: ANILLOS -----
-//
-
-fn draw_jupiter_rings(fb: &mut FrameBuffer, mvp: &glm::Mat4, inner_radius: f32, outer_radius: f32) {
-    let segments = 128;
-    let w = WIDTH as f32;
-    let h = HEIGHT as f32;
-
-    let project = |v: glm::Vec3| -> Vec3 {
-        let p = glm::vec4(v.x, v.y, v.z, 1.0);
-        let clip = *mvp * p;
-        if clip.w.abs() < 1e-6 {
-            return glm::vec3(-9999.0, -9999.0, 1.0);
-        }
-        let ndc = glm::vec3(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w);
-        let sx = (ndc.x * 0.5 + 0.5) * w;
-        let sy = (1.0 - (ndc.y * 0.5 + 0.5)) * h;
-        glm::vec3(sx, sy, ndc.z)
-    };
-
-    let mut inner_pts = Vec::with_capacity(segments + 1);
-    let mut outer_pts = Vec::with_capacity(segments + 1);
-
-    for i in 0..=segments {
-        let t = i as f32 / segments as f32;
-        let a = t * std::f32::consts::TAU;
-
-        let v_inner = glm::vec3(inner_radius * a.cos(), 0.0, inner_radius * a.sin());
-        let v_outer = glm::vec3(outer_radius * a.cos(), 0.0, outer_radius * a.sin());
-
-        inner_pts.push(project(v_inner));
-        outer_pts.push(project(v_outer));
-    }
-
-    for i in 0..segments {
-        let p0 = inner_pts[i];
-        let p1 = outer_pts[i];
-        let p2 = inner_pts[i + 1];
-        let p3 = outer_pts[i + 1];
-
-        let t = i as f32 / segments as f32;
-        let stripe = (t * 12.0).sin() * 0.5 + 0.5;
-
-        let base = glm::vec3(0.78, 0.80, 0.90);
-        let accent = glm::vec3(0.35, 0.85, 1.20);
-        let col = base * (1.0 - stripe * 0.5) + accent * (stripe * 0.9);
-
-        let r = (col.x.clamp(0.0, 1.4) * 255.0) as u8;
-        let g = (col.y.clamp(0.0, 1.4) * 255.0) as u8;
-        let b = (col.z.clamp(0.0, 1.4) * 255.0) as u8;
-        fb.set_color(Color::new(r, g, b, 255));
-
-        triangle_filled(fb, &p0, &p1, &p2);
-        triangle_filled(fb, &p2, &p1, &p3);
-    }
-
-    fb.set_color(Color::new(230, 240, 255, 255));
-
-    for i in 0..segments {
-        let a = inner_pts[i];
-        let b = inner_pts[i + 1];
-        line_depth_thick(fb, &a, &b, 1);
-    }
-    for i in 0..segments {
-        let a = outer_pts[i];
-        let b = outer_pts[i + 1];
-        line_depth_thick(fb, &a, &b, 1);
-    }
-}
-
 //
 // ----- NAVE MILANO: PALETA Y COLOR POR CARA -----
 //
 
 fn choose_milano_color(
-    va: Vec3,
-    vb: Vec3,
-    vc: Vec3,
+    p: Vec3,
     n_view: glm::Vec3,
     center: glm::Vec3,
     size: glm::Vec3,
@@ -212,8 +153,8 @@ fn choose_milano_color(
 
     let up_view = glm::vec3(0.0, 1.0, 0.0);
 
-    let u = (((va.x + vb.x + vc.x) / 3.0) - center.x) / size.x;
-    let v = (((va.z + vb.z + vc.z) / 3.0) - center.z) / size.z;
+    let u = (p.x - center.x) / size.x;
+    let v = (p.z - center.z) / size.z;
 
     let wing_u_thresh_base = 0.24;
     let wing_u_curve = 0.08;
@@ -258,99 +199,154 @@ fn choose_milano_color(
     }
 }
 
+fn is_star(inst: &Instance) -> bool {
+    matches!(inst.kind, BodyShader::Star)
+}
+
+//
+// ----- ESCENA: DATOS DERIVADOS (se recalculan al recargar) -----
+//
+
+// Objetos seleccionables con Tab: Sol, planetas con sus lunas (en el orden de
+// `instances`), el cinturón y la nave. RenderModes los identifica por su índice en
+// esta lista; los nombres repetidos se numeran para distinguirlos en el HUD.
+fn render_targets(scene: &Scene) -> Vec<String> {
+    let mut labels = vec![BodyShader::Star.label()];
+    for p in &scene.planets {
+        labels.push(p.kind.label());
+        for m in &p.moons {
+            labels.push(m.kind.label());
+        }
+    }
+    if scene.belt.is_some() {
+        labels.push(BELT_LABEL);
+    }
+    labels.push(SHIP_LABEL);
+
+    let count = |labels: &[&str], label: &str| labels.iter().filter(|l| **l == label).count();
+    labels
+        .iter()
+        .enumerate()
+        .map(|(i, label)| match count(&labels, label) {
+            1 => label.to_string(),
+            _ => format!("{label} {}", count(&labels[..=i], label)),
+        })
+        .collect()
+}
+
+// near fuera de la superficie del Sol; far más allá de la órbita más externa
+fn scene_shadow_map(scene: &Scene, sphere_radius: f32) -> ShadowMap {
+    ShadowMap::new(
+        SHADOW_MAP_SIZE,
+        sphere_radius * scene.sun_scale,
+        sphere_radius * scene.orbit_base * (scene.max_orbit() + 0.6),
+    )
+}
+
 //
 // ----- MAIN FINAL -----
 //
 
 fn main() -> anyhow::Result<()> {
+    // Argumentos: [esfera.obj] [--bench-sun [frames]] [--profile-csv archivo.csv] [--seed n]
+    //             [--shaders archivo.cfg] [--scene archivo.cfg] [--stars catalogo.txt]
+    let mut sphere_path = "assets/sphere.obj".to_string();
+    let mut shader_params_path = SHADER_PARAMS_PATH.to_string();
+    let mut scene_path = SCENE_PATH.to_string();
+    let mut star_catalog_path = STAR_CATALOG_PATH.to_string();
+    let mut bench_frames: Option<usize> = None;
+    let mut profile_csv: Option<String> = None;
+    let mut planet_seed = DEFAULT_PLANET_SEED;
+    let mut args = std::env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench-sun" => {
+                let frames = args.next_if(|a| a.parse::<usize>().is_ok());
+                bench_frames = Some(frames.and_then(|a| a.parse().ok()).unwrap_or(120));
+            }
+            "--profile-csv" => {
+                profile_csv = Some(args.next().unwrap_or_else(|| "profile.csv".to_string()));
+            }
+            "--shaders" => {
+                shader_params_path = args.next().unwrap_or(shader_params_path);
+            }
+            "--scene" => {
+                scene_path = args.next().unwrap_or(scene_path);
+            }
+            "--stars" => {
+                star_catalog_path = args.next().unwrap_or(star_catalog_path);
+            }
+            "--seed" => {
+                let value = args.next().unwrap_or_default();
+                planet_seed = value.parse().map_err(|_| {
+                    anyhow::anyhow!("--seed espera un entero, se recibió '{value}'")
+                })?;
+            }
+            _ => sphere_path = arg,
+        }
+    }
+
+    if let Some(frames) = bench_frames {
+        return bench::run_sun_bench(&sphere_path, frames);
+    }
+
+    // Colores y frecuencias de los shaders y sistema planetario: se recargan al
+    // guardar el archivo; si tiene errores se sigue con la última versión válida
+    let load_shader_params = |path: &str| ShaderParams::load(path);
+    // Los nombres de `kind` en la escena se resuelven con el registro de shaders
+    let shader_registry = ShaderRegistry::with_builtins();
+    let load_scene = |path: &str| Scene::load(path, &shader_registry, planet_seed);
+    let mut shader_file =
+        Reloadable::new(&shader_params_path, load_shader_params, ShaderParams::default);
+    let mut scene_file = Reloadable::new(&scene_path, load_scene, || Scene::builtin(planet_seed));
+    // Catálogo de estrellas del fondo; guardar él o su constellations.txt lo recarga
+    let load_sky = |path: &str| Starfield::load(path);
+    let mut sky_file = Reloadable::new(&star_catalog_path, load_sky, Starfield::procedural)
+        .also_watching(&Starfield::constellations_path(&star_catalog_path));
+    // Vía Láctea y nebulosas horneadas; se rehornean si cambian los parámetros del cielo
+    let mut sky_box = SkyBox::bake(&shader_file.value.sky);
+    for error in [&shader_file.error, &scene_file.error, &sky_file.error]
+        .into_iter()
+        .flatten()
+    {
+        eprintln!("{error}");
+    }
+    // Aviso temporal tras una recarga correcta (ruta, segundos restantes)
+    let mut reload_notice: Option<(String, f32)> = None;
+
     let (mut rl, thread) = raylib::init()
-        .size(WIDTH, HEIGHT)
+        .size(INITIAL_WIDTH, INITIAL_HEIGHT)
+        .resizable()
         .title("SpaceTravel – Comic Solar System")
         .build();
     rl.set_target_fps(120);
+    rl.set_window_min_size(320, 240);
 
-    let mut fb = FrameBuffer::new(WIDTH, HEIGHT, Color::BLACK);
+    let mut scale_idx = 0;
+    let mut fb = FrameBuffer::new(INITIAL_WIDTH, INITIAL_HEIGHT, Color::BLACK);
 
     // ----- Esfera base (sol/planetas/lunas) -----
-    let sphere_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "assets/sphere.obj".to_string());
-    let (sphere_vertices, sphere_faces) = load_obj(&sphere_path)?;
+    let sphere = Mesh::load(&sphere_path)?;
     println!(
         "Sphere mesh -> Vértices: {} | Caras: {}",
-        sphere_vertices.len(),
-        sphere_faces.len()
+        sphere.vertices.len(),
+        sphere.faces.len()
     );
-
-    let (mut s_min, mut s_max) = (
-        glm::vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY),
-        glm::vec3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
-    );
-    for v in &sphere_vertices {
-        s_min.x = s_min.x.min(v.x);
-        s_min.y = s_min.y.min(v.y);
-        s_min.z = s_min.z.min(v.z);
-        s_max.x = s_max.x.max(v.x);
-        s_max.y = s_max.y.max(v.y);
-        s_max.z = s_max.z.max(v.z);
-    }
-    let sphere_center = (s_min + s_max) * 0.5;
-    let s_size = s_max - s_min;
-    let sphere_radius =
-        0.5 * (s_size.x * s_size.x + s_size.y * s_size.y + s_size.z * s_size.z).sqrt();
-
-    let mut sphere_edge_to_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
-    for (fi, f) in sphere_faces.iter().enumerate() {
-        let (i0, i1, i2) = (
-            f.vertex_indices[0],
-            f.vertex_indices[1],
-            f.vertex_indices[2],
-        );
-        for &(a, b) in &[(i0, i1), (i1, i2), (i2, i0)] {
-            let key = if a < b { (a, b) } else { (b, a) };
-            sphere_edge_to_faces.entry(key).or_default().push(fi);
-        }
-    }
+    let sphere_center = sphere.center;
+    let sphere_radius = sphere.bounding_radius();
 
     // ----- Nave Milano -----
-    let (ship_vertices, ship_faces) = load_obj("assets/Nave_espacial.obj")?;
+    let ship = Mesh::load("assets/Nave_espacial.obj")?;
     println!(
         "Ship mesh   -> Vértices: {} | Caras: {}",
-        ship_vertices.len(),
-        ship_faces.len()
-    );
-
-    let (mut sh_min, mut sh_max) = (
-        glm::vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY),
-        glm::vec3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        ship.vertices.len(),
+        ship.faces.len()
     );
-    for v in &ship_vertices {
-        sh_min.x = sh_min.x.min(v.x);
-        sh_min.y = sh_min.y.min(v.y);
-        sh_min.z = sh_min.z.min(v.z);
-        sh_max.x = sh_max.x.max(v.x);
-        sh_max.y = sh_max.y.max(v.y);
-        sh_max.z = sh_max.z.max(v.z);
-    }
-    let ship_center = (sh_min + sh_max) * 0.5;
-    let ship_size = sh_max - sh_min;
-
-    let mut ship_edge_to_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
-    for (fi, f) in ship_faces.iter().enumerate() {
-        let (i0, i1, i2) = (
-            f.vertex_indices[0],
-            f.vertex_indices[1],
-            f.vertex_indices[2],
-        );
-        for &(a, b) in &[(i0, i1), (i1, i2), (i2, i0)] {
-            let key = if a < b { (a, b) } else { (b, a) };
-            ship_edge_to_faces.entry(key).or_default().push(fi);
-        }
-    }
+    let ship_center = ship.center;
+    let ship_size = ship.size;
 
     // ----- Cámara -----
     let mut cam = Camera::default();
-    let aspect = (WIDTH as f32) / (HEIGHT as f32);
 
     let mut dist = if (cam.fov_y * 0.5).tan() > 1e-6 {
         sphere_radius / (cam.fov_y * 0.5).tan()
@@ -361,53 +357,6 @@ fn main() -> anyhow::Result<()> {
     cam.pos = glm::vec3(0.0, 8.0, dist);
     cam.zfar = 5000.0;
 
-    // ----- Sistema planetario -----
-    let sun_scale = 3.0;
-
-    let orbit_base = sphere_radius * 6.0;
-    let planets = [
-        PlanetDef {
-            kind: BodyShader::Mercury,
-            orbit_radius: orbit_base * 1.0,
-            orbit_speed: 0.6,
-            self_speed: 2.0,
-            scale: 0.45,
-            has_moon: false,
-        },
-        PlanetDef {
-            kind: BodyShader::Venus,
-            orbit_radius: orbit_base * 1.6,
-            orbit_speed: 0.45,
-            self_speed: 1.6,
-            scale: 0.8,
-            has_moon: false,
-        },
-        PlanetDef {
-            kind: BodyShader::Rocky,
-            orbit_radius: orbit_base * 2.3,
-            orbit_speed: 0.35,
-            self_speed: 1.8,
-            scale: 0.9,
-            has_moon: true,
-        },
-        PlanetDef {
-            kind: BodyShader::Mars,
-            orbit_radius: orbit_base * 3.1,
-            orbit_speed: 0.25,
-            self_speed: 1.5,
-            scale: 0.75,
-            has_moon: false,
-        },
-        PlanetDef {
-            kind: BodyShader::GasGiant,
-            orbit_radius: orbit_base * 4.3,
-            orbit_speed: 0.18,
-            self_speed: 1.2,
-            scale: 1.7,
-            has_moon: false,
-        },
-    ];
-
     // ----- Warp -----
     let mut warp = WarpState {
         active: false,
@@ -418,9 +367,112 @@ fn main() -> anyhow::Result<()> {
 
     let mut time_acc: f32 = 0.0;
 
+    // Textura de presentación: se crea una vez y se actualiza cada frame
+    let mut screen_tex: Option<Texture2D> = None;
+
+    // ----- Modos de render (global o por objeto) -----
+    let mut render_modes = RenderModes::new(RenderMode::Shaded);
+    let mut mode_targets = render_targets(&scene_file.value);
+    // 0 = global; i > 0 = mode_targets[i - 1]
+    let mut mode_sel = 0;
+    let mut shading = ShadingMode::Flat;
+
+    // ----- Sombras desde el Sol -----
+    let mut shadow_map = scene_shadow_map(&scene_file.value, sphere_radius);
+
+    // ----- Cinturón de asteroides (mallas compartidas; se rehace al recargar) -----
+    let mut belt_field = scene_file.value.belt.as_ref().map(AsteroidField::new);
+
+    // ----- Perfilador -----
+    let mut prof = Profiler::new();
+    let mut show_stats = false;
+    let mut show_constellations = true;
+    if let Some(path) = &profile_csv {
+        prof.start_csv(path)?;
+        println!("Grabando perfil por frame en {path}");
+    }
+
     while !rl.window_should_close() {
         let dt = rl.get_frame_time();
         time_acc += dt;
+        prof.begin_frame();
+        let t_sim = prof.start();
+
+        // ----- Recarga en caliente (sin tocar cámara ni tiempo de simulación) -----
+        if shader_file.update(load_shader_params) {
+            if !sky_box.is_baked_with(&shader_file.value.sky) {
+                sky_box = SkyBox::bake(&shader_file.value.sky);
+            }
+            reload_notice = Some((shader_file.path().to_string(), RELOAD_NOTICE_SECS));
+        }
+        if scene_file.update(load_scene) {
+            mode_targets = render_targets(&scene_file.value);
+            mode_sel = mode_sel.min(mode_targets.len());
+            shadow_map = scene_shadow_map(&scene_file.value, sphere_radius);
+            belt_field = scene_file.value.belt.as_ref().map(AsteroidField::new);
+            reload_notice = Some((scene_file.path().to_string(), RELOAD_NOTICE_SECS));
+        }
+        if sky_file.update(load_sky) {
+            reload_notice = Some((sky_file.path().to_string(), RELOAD_NOTICE_SECS));
+        }
+        let shader_params = &shader_file.value;
+        let scene = &scene_file.value;
+        let sun_scale = scene.sun_scale;
+        let orbit_base = sphere_radius * scene.orbit_base;
+
+        // ----- Ventana: pantalla completa y escala de render -----
+        {
+            use raylib::consts::KeyboardKey::*;
+
+            if rl.is_key_pressed(KEY_F11) {
+                if rl.is_window_fullscreen() {
+                    rl.toggle_fullscreen();
+                    rl.set_window_size(INITIAL_WIDTH, INITIAL_HEIGHT);
+                } else {
+                    // Ajusta la ventana al monitor antes de pasar a pantalla completa
+                    let monitor = get_current_monitor();
+                    rl.set_window_size(get_monitor_width(monitor), get_monitor_height(monitor));
+                    rl.toggle_fullscreen();
+                }
+            }
+            if rl.is_key_pressed(KEY_R) {
+                scale_idx = (scale_idx + 1) % RENDER_SCALES.len();
+            }
+            if rl.is_key_pressed(KEY_TAB) {
+                mode_sel = (mode_sel + 1) % (mode_targets.len() + 1);
+            }
+            if rl.is_key_pressed(KEY_V) {
+                render_modes.cycle(mode_sel.checked_sub(1));
+            }
+            if rl.is_key_pressed(KEY_G) {
+                shading = shading.next();
+            }
+            if rl.is_key_pressed(KEY_C) {
+                show_constellations = !show_constellations;
+            }
+            if rl.is_key_pressed(KEY_F3) {
+                show_stats = !show_stats;
+            }
+            if rl.is_key_pressed(KEY_F4) {
+                if prof.is_recording() {
+                    prof.stop_csv();
+                    println!("Perfil guardado");
+                } else if let Err(e) = prof.start_csv("profile.csv") {
+                    eprintln!("Error creando profile.csv: {e}");
+                } else {
+                    println!("Grabando perfil por frame en profile.csv");
+                }
+            }
+        }
+
+        let screen_w = rl.get_screen_width().max(1);
+        let screen_h = rl.get_screen_height().max(1);
+        let render_scale = RENDER_SCALES[scale_idx];
+        fb.resize(
+            (screen_w as f32 * render_scale).round() as i32,
+            (screen_h as f32 * render_scale).round() as i32,
+        );
+        let aspect = (fb.width as f32) / (fb.height as f32);
 
         // Input normal sólo si no estamos en warp
         if !warp.active {
@@ -445,73 +497,90 @@ fn main() -> anyhow::Result<()> {
 
         instances.push(Instance {
             kind: BodyShader::Star,
+            target: instances.len(),
             model: sun_model,
             center_world: sun_center_world,
             radius_collision: sun_collision_radius,
+            rings: None,
         });
         collision_spheres.push((sun_center_world, sun_collision_radius));
 
-        // Planetas + luna de la Tierra
+        // Planetas con sus lunas y anillos
         let mut earth_center_world = glm::vec3(0.0, 0.0, 0.0);
-        let mut jupiter_model_for_rings: Option<glm::Mat4> = None;
-        let mut jupiter_radius_world = 0.0;
 
-        for (idx, p) in planets.iter().enumerate() {
+        for (idx, p) in scene.planets.iter().enumerate() {
             let angle = time_acc * p.orbit_speed + idx as f32 * 0.7;
-            let center_world = glm::vec3(
-                p.orbit_radius * angle.cos(),
-                0.0,
-                p.orbit_radius * angle.sin(),
-            );
+            let orbit_radius = orbit_base * p.orbit;
+            let center_world =
+                glm::vec3(orbit_radius * angle.cos(), 0.0, orbit_radius * angle.sin());
 
+            // Eje de giro inclinado (con precesión): el giro propio es alrededor de él
+            let axis = p.axis.orientation(time_acc);
             let model = glm::translation(&center_world)
+                * axis
                 * glm::rotation(time_acc * p.self_speed, &glm::vec3(0.0, 1.0, 0.0))
                 * glm::scaling(&glm::vec3(p.scale, p.scale, p.scale))
                 * glm::translation(&(-sphere_center));
 
             let coll_r = sphere_radius * p.scale * 1.2;
+            let rings = p.rings.as_ref().map(|r| {
+                RingInstance::new(r, center_world, &axis, sphere_radius * p.scale)
+            });
             instances.push(Instance {
-                kind: p.kind,
+                kind: p.kind.clone(),
+                target: instances.len(),
                 model,
                 center_world,
                 radius_collision: coll_r,
+                rings,
             });
             collision_spheres.push((center_world, coll_r));
 
             if let BodyShader::Rocky = p.kind {
                 earth_center_world = center_world;
+            }
 
-                // Luna
-                let moon_scale = 0.35;
-                let moon_orbit_radius = sphere_radius * p.scale * 3.0;
-                let moon_angle = time_acc * 1.6;
-
-                let moon_offset = glm::vec3(
-                    moon_orbit_radius * moon_angle.cos(),
-                    sphere_radius * 0.3 * (moon_angle * 0.8).sin(),
-                    moon_orbit_radius * moon_angle.sin(),
-                );
+            // Lunas, en el plano del ecuador del planeta
+            let equator = glm::mat4_to_mat3(&axis);
+            for m in &p.moons {
+                let moon_orbit_radius = sphere_radius * p.scale * m.orbit_factor;
+                let moon_angle = time_acc * m.orbit_speed + m.phase;
+
+                let moon_offset = equator
+                    * glm::vec3(
+                        moon_orbit_radius * moon_angle.cos(),
+                        sphere_radius * m.bob * (moon_angle * 0.8).sin(),
+                        moon_orbit_radius * moon_angle.sin(),
+                    );
                 let moon_center_world = center_world + moon_offset;
 
+                let moon_axis = m.axis.orientation(time_acc);
                 let moon_model = glm::translation(&moon_center_world)
-                    * glm::rotation(time_acc * 1.2, &glm::vec3(0.0, 1.0, 0.0))
-                    * glm::scaling(&glm::vec3(moon_scale, moon_scale, moon_scale))
+                    * moon_axis
+                    * glm::rotation(time_acc * m.self_speed, &glm::vec3(0.0, 1.0, 0.0))
+                    * glm::scaling(&glm::vec3(m.scale, m.scale, m.scale))
                     * glm::translation(&(-sphere_center));
 
-                let moon_coll = sphere_radius * moon_scale * 1.3;
+                let moon_coll = sphere_radius * m.scale * 1.3;
+                let moon_rings = m.rings.as_ref().map(|r| {
+                    RingInstance::new(r, moon_center_world, &moon_axis, sphere_radius * m.scale)
+                });
                 instances.push(Instance {
-                    kind: BodyShader::Moon,
+                    kind: m.kind.clone(),
+                    target: instances.len(),
                     model: moon_model,
                     center_world: moon_center_world,
                     radius_collision: moon_coll,
+                    rings: moon_rings,
                 });
                 collision_spheres.push((moon_center_world, moon_coll));
             }
+        }
 
-            if let BodyShader::GasGiant = p.kind {
-                jupiter_model_for_rings = Some(model);
-                jupiter_radius_world = sphere_radius * p.scale;
-            }
+        // Cinturón de asteroides: cada roca en su órbita, todas chocan con la cámara
+        if let Some(field) = &mut belt_field {
+            field.update(time_acc, orbit_base, sphere_radius);
+            collision_spheres.extend(field.collision_spheres());
         }
 
         // ----- Warp: teclas a distintos puntos -----
@@ -521,7 +590,7 @@ fn main() -> anyhow::Result<()> {
 
             // 1 -> vista general arriba del plano
             if rl.is_key_pressed(KEY_ONE) {
-                let far = orbit_base * 6.0;
+                let far = orbit_base * (scene.max_orbit() + 0.6);
                 warp_target = Some(glm::vec3(0.0, far * 0.45, far));
             }
             // 2 -> sol
@@ -563,7 +632,7 @@ fn main() -> anyhow::Result<()> {
             }
             // 6 -> justo sobre el plano eclíptico
             if rl.is_key_pressed(KEY_SIX) {
-                let far = orbit_base * 5.0;
+                let far = orbit_base * (scene.max_orbit() - 0.4);
                 warp_target = Some(glm::vec3(-far, 3.0, 0.0));
             }
 
@@ -615,198 +684,323 @@ fn main() -> anyhow::Result<()> {
             * glm::scaling(&glm::vec3(ship_scale, ship_scale, ship_scale))
             * glm::translation(&(-ship_center));
 
-        let ship_mvp = proj * view * ship_model;
-        let ship_mv = view * ship_model;
-
-        let ship_screen_vertices: Vec<Vec3> =
-            project_vertices_perspective(&ship_vertices, &ship_mvp, WIDTH, HEIGHT);
-
-        let ship_view_vertices: Vec<glm::Vec3> = ship_vertices
-            .iter()
-            .map(|v| {
-                let p = ship_mv * glm::vec4(v.x, v.y, v.z, 1.0);
-                glm::vec3(p.x, p.y, p.z)
-            })
-            .collect();
+        prof.record("simulacion", t_sim);
 
-        let mut ship_is_front = vec![false; ship_faces.len()];
-        for (fi, f) in ship_faces.iter().enumerate() {
-            let (i0, i1, i2) = (
-                f.vertex_indices[0],
-                f.vertex_indices[1],
-                f.vertex_indices[2],
-            );
-            let va = ship_view_vertices[i0];
-            let vb = ship_view_vertices[i1];
-            let vc = ship_view_vertices[i2];
-            let n = glm::normalize(&glm::cross(&(vb - va), &(vc - va)));
-            ship_is_front[fi] = n.z < 0.0;
+        // Mapa de sombras: todos los cuerpos menos el Sol + las bandas densas de los anillos
+        let t_shadow = prof.start();
+        shadow_map.clear();
+        for inst in instances.iter().filter(|i| !matches!(i.kind, BodyShader::Star)) {
+            shadow_map.draw_mesh(&sphere, &inst.model);
+            if let Some(rings) = &inst.rings {
+                rings.draw_shadow(&mut shadow_map);
+            }
         }
+        prof.record("sombras", t_shadow);
+
+        let t_proj = prof.start();
+        let ship_pm = project_mesh(&ship, &(view * ship_model), &proj, &fb);
+        prof.record("proyeccion", t_proj);
 
         // ----- Render: limpiar framebuffer -----
         fb.clear();
 
         // Órbitas siempre al fondo
-        for p in &planets {
+        let t_orbits = prof.start();
+        for p in &scene.planets {
             let orbit_color = Color::new(60, 90, 130, 255);
-            draw_orbit(&mut fb, &view, &proj, p.orbit_radius, orbit_color);
+            draw_orbit(&mut fb, &view, &proj, orbit_base * p.orbit, orbit_color);
         }
+        prof.record("orbitas", t_orbits);
 
-        // ----- Relleno Sol / Planetas / Lunas -----
+        // ----- Sol / Planetas / Lunas (según modo de render) -----
         for inst in &instances {
-            let mvp = proj * view * inst.model;
-            let mv = view * inst.model;
-
-            let screen_vertices: Vec<Vec3> =
-                project_vertices_perspective(&sphere_vertices, &mvp, WIDTH, HEIGHT);
-
-            let view_vertices: Vec<glm::Vec3> = sphere_vertices
-                .iter()
-                .map(|v| {
-                    let p = mv * glm::vec4(v.x, v.y, v.z, 1.0);
-                    glm::vec3(p.x, p.y, p.z)
-                })
-                .collect();
-
-            let mut is_front = vec![false; sphere_faces.len()];
-            for (fi, f) in sphere_faces.iter().enumerate() {
-                let (i0, i1, i2) = (
-                    f.vertex_indices[0],
-                    f.vertex_indices[1],
-                    f.vertex_indices[2],
-                );
-                let va = view_vertices[i0];
-                let vb = view_vertices[i1];
-                let vc = view_vertices[i2];
-                let n = glm::normalize(&glm::cross(&(vb - va), &(vc - va)));
-                is_front[fi] = n.z < 0.0;
-            }
-
-            for f in &sphere_faces {
-                let (i0, i1, i2) = (
-                    f.vertex_indices[0],
-                    f.vertex_indices[1],
-                    f.vertex_indices[2],
-                );
-
-                let a = screen_vertices[i0];
-                let b = screen_vertices[i1];
-                let c = screen_vertices[i2];
-
-                let va_m = sphere_vertices[i0];
-                let vb_m = sphere_vertices[i1];
-                let vc_m = sphere_vertices[i2];
-                let va_v = view_vertices[i0];
-                let vb_v = view_vertices[i1];
-                let vc_v = view_vertices[i2];
-
-                let n_view = glm::normalize(&glm::cross(&(vb_v - va_v), &(vc_v - va_v)));
-                let centroid = (va_m + vb_m + vc_m) / 3.0;
-
-                let color = shade_body(
-                    inst.kind,
-                    centroid,
-                    n_view,
+            let t_proj = prof.start();
+            let pm = project_mesh(&sphere, &(view * inst.model), &proj, &fb);
+            prof.record("proyeccion", t_proj);
+
+            let t_shade = prof.start();
+            let sun_dir = sun_direction_model(&inst.model, inst.center_world, sun_center_world);
+            let normal_to_world = glm::mat4_to_mat3(&inst.model);
+            let surface = Surface::new(&sphere, &(view * inst.model), shading, |frag| {
+                let p_world = inst.model * glm::vec4(frag.p.x, frag.p.y, frag.p.z, 1.0);
+                let n_world = glm::normalize(&(normal_to_world * frag.n));
+                let sun = SunLight {
+                    dir: sun_dir,
+                    visibility: shadow_map
+                        .visibility(glm::vec3(p_world.x, p_world.y, p_world.z), n_world),
+                };
+                shade_body(
+                    &inst.kind,
+                    shader_params,
+                    frag,
                     sphere_center,
                     sphere_radius,
                     time_acc,
-                );
+                    &sun,
+                )
+            });
+            prof.record("sombreado", t_shade);
 
-                fb.set_color(color);
-                triangle_filled(&mut fb, &a, &b, &c);
-            }
+            let mode = render_modes.mode_for(inst.target);
+            // el Sol sin contorno: su borde se funde con la corona
+            let outline = if is_star(inst) { NO_OUTLINE } else { 2 };
+            draw_mesh(&mut fb, &pm, &surface, mode, outline, &mut prof);
+        }
 
-            // Contorno estilo cómic
-            fb.set_color(Color::BLACK);
-            let thickness = 2;
-            for (&(i0, i1), adj) in &sphere_edge_to_faces {
-                let draw = match adj.as_slice() {
-                    [f0] => is_front[*f0],
-                    [f0, f1] => is_front[*f0] ^ is_front[*f1],
-                    _ => false,
+        // ----- Nave Milano -----
+        let t_shade = prof.start();
+        let ship_sun_dir = sun_direction_model(&ship_model, ship_pos, sun_center_world);
+        let ship_surface = Surface::new(&ship, &(view * ship_model), shading, |frag| {
+            let base = choose_milano_color(frag.p, frag.n_view, ship_center, ship_size);
+            // iluminación a dos caras: el OBJ de la nave no tiene el winding consistente
+            let ndl = glm::dot(&frag.n, &ship_sun_dir).abs();
+            let k = SHIP_AMBIENT + (1.0 - SHIP_AMBIENT) * ndl;
+            let lit = |c: u8| (c as f32 * k).min(255.0) as u8;
+            Color::new(lit(base.r), lit(base.g), lit(base.b), 255)
+        });
+        prof.record("sombreado", t_shade);
+
+        // la nave es el último objeto de `mode_targets` y el cinturón, si hay, el penúltimo
+        let ship_target = mode_targets.len() - 1;
+        let ship_mode = render_modes.mode_for(ship_target);
+        draw_mesh(&mut fb, &ship_pm, &ship_surface, ship_mode, 2, &mut prof);
+
+        // ----- Asteroides: mallas de cerca (dos niveles de detalle), puntos de lejos -----
+        let t_belt = prof.start();
+        let belt_rocks = belt_field
+            .as_ref()
+            .map(|field| field.visible(&view, &proj, &fb, sun_center_world));
+        prof.record("asteroides", t_belt);
+        let belt_mode = render_modes.mode_for(ship_target - 1);
+        for rock in belt_rocks.iter().flat_map(|rocks| &rocks.meshes) {
+            let mv = view * rock.model;
+            let t_proj = prof.start();
+            let pm = project_mesh(rock.mesh, &mv, &proj, &fb);
+            prof.record("proyeccion", t_proj);
+
+            let t_shade = prof.start();
+            let sun_dir = sun_direction_model(&rock.model, rock.center, sun_center_world);
+            let surface = Surface::new(rock.mesh, &mv, shading, |frag| {
+                let p_world = rock.model * glm::vec4(frag.p.x, frag.p.y, frag.p.z, 1.0);
+                let n_world = glm::normalize(&(glm::mat4_to_mat3(&rock.model) * frag.n));
+                let sun = SunLight {
+                    dir: sun_dir,
+                    visibility: shadow_map.visibility(p_world.xyz(), n_world),
                 };
-                if draw {
-                    let a = screen_vertices[i0];
-                    let b = screen_vertices[i1];
-                    line_depth_thick(&mut fb, &a, &b, thickness);
-                }
-            }
-        }
+                rock.shade(frag, &sun)
+            });
+            prof.record("sombreado", t_shade);
 
-        // Anillos de Júpiter (usa el modelo que calculamos arriba)
-        if let Some(j_model) = jupiter_model_for_rings {
-            let mvp = proj * view * j_model;
-            let inner = jupiter_radius_world * 0.50;
-            let outer = jupiter_radius_world * 1.00;
-            draw_jupiter_rings(&mut fb, &mvp, inner, outer);
+            draw_mesh(&mut fb, &pm, &surface, belt_mode, rock.outline, &mut prof);
         }
 
-        // ----- Nave Milano (relleno + contorno) -----
-        for f in &ship_faces {
-            let (i0, i1, i2) = (
-                f.vertex_indices[0],
-                f.vertex_indices[1],
-                f.vertex_indices[2],
-            );
-
-            let a = ship_screen_vertices[i0];
-            let b = ship_screen_vertices[i1];
-            let c = ship_screen_vertices[i2];
-
-            let va_m = ship_vertices[i0];
-            let vb_m = ship_vertices[i1];
-            let vc_m = ship_vertices[i2];
-            let va_v = ship_view_vertices[i0];
-            let vb_v = ship_view_vertices[i1];
-            let vc_v = ship_view_vertices[i2];
-
-            let n_view = glm::normalize(&glm::cross(&(vb_v - va_v), &(vc_v - va_v)));
-            let base_color = choose_milano_color(va_m, vb_m, vc_m, n_view, ship_center, ship_size);
-
-            fb.set_color(base_color);
-            triangle_filled(&mut fb, &a, &b, &c);
+        // ----- Cielo y estrellas: detrás de todo lo opaco, antes de los translúcidos -----
+        let t_stars = prof.start();
+        sky_box.draw(&mut fb, &view, &proj, shader_params.sky.intensity);
+        sky_file
+            .value
+            .draw(&mut fb, &view, &proj, show_constellations);
+        prof.record("cielo", t_stars);
+
+        // Rocas lejanas mezcladas sobre el cielo (la geometría opaca las tapa)
+        if let Some(rocks) = &belt_rocks {
+            let t_points = prof.start();
+            rocks.draw_points(&mut fb);
+            prof.record("asteroides", t_points);
         }
 
-        fb.set_color(Color::BLACK);
-        let ship_thickness = 2;
-        for (&(i0, i1), adj) in &ship_edge_to_faces {
-            let draw = match adj.as_slice() {
-                [f0] => ship_is_front[*f0],
-                [f0, f1] => ship_is_front[*f0] ^ ship_is_front[*f1],
-                _ => false,
+        // ----- Translúcidos: corona, anillos, nubes y atmósferas de lejos a cerca -----
+        let shell_shading = if shading == ShadingMode::Phong {
+            ShadingMode::Phong
+        } else {
+            ShadingMode::Gouraud
+        };
+        let shows_shells = |i: &Instance| {
+            render_modes.mode_for(i.target).shows_surface()
+                && !i.kind.shells(shader_params).is_empty()
+        };
+        let shows_corona =
+            |i: &Instance| is_star(i) && render_modes.mode_for(i.target).shows_surface();
+        let mut translucent: Vec<&Instance> = instances
+            .iter()
+            .filter(|i| i.rings.is_some() || shows_shells(i) || shows_corona(i))
+            .collect();
+        translucent.sort_by(|a, b| {
+            let da = glm::distance2(&a.center_world, &cam.pos);
+            let db = glm::distance2(&b.center_world, &cam.pos);
+            db.total_cmp(&da)
+        });
+        let view3 = glm::mat4_to_mat3(&view);
+        let view_proj = proj * view;
+        let sun_radius_world = sphere_radius * sun_scale;
+        let sun_screen = SunOnScreen::project(
+            sun_center_world,
+            sun_radius_world,
+            cam.pos,
+            cam.right(),
+            &view_proj,
+            &fb,
+        );
+        for inst in translucent {
+            if shows_corona(inst) {
+                if let Some(sun) = &sun_screen {
+                    let t_glare = prof.start();
+                    draw_corona(&mut fb, sun, &shader_params.star_corona, time_acc);
+                    prof.record("corona", t_glare);
+                }
+                continue;
+            }
+
+            // Mitad trasera del anillo, cascos del cuerpo y mitad delantera
+            let draw_rings = |fb: &mut FrameBuffer, prof: &mut Profiler, half| {
+                if let Some(rings) = &inst.rings {
+                    let t_rings = prof.start();
+                    rings.draw(fb, &view_proj, cam.pos, sun_center_world, &shadow_map, half);
+                    prof.record("anillos", t_rings);
+                }
             };
-            if draw {
-                let a = ship_screen_vertices[i0];
-                let b = ship_screen_vertices[i1];
-                line_depth_thick(&mut fb, &a, &b, ship_thickness);
+            draw_rings(&mut fb, &mut prof, RingHalf::Behind);
+            if shows_shells(inst) {
+                let ray_view = glm::normalize(&(view3 * (inst.center_world - cam.pos)));
+                let sun_view = glm::normalize(&(view3 * (sun_center_world - inst.center_world)));
+
+                // De dentro hacia fuera: cada casco se mezcla sobre el anterior
+                for layer in inst.kind.shells(shader_params) {
+                    let k = layer.scale();
+                    let shell_model = inst.model
+                        * glm::translation(&sphere_center)
+                        * glm::scaling(&glm::vec3(k, k, k))
+                        * glm::translation(&(-sphere_center));
+                    let mv = view * shell_model;
+                    let pm = project_mesh(&sphere, &mv, &proj, &fb);
+
+                    let sun_dir =
+                        sun_direction_model(&shell_model, inst.center_world, sun_center_world);
+                    let normal_to_world = glm::mat4_to_mat3(&shell_model);
+                    let surface = Surface::new(&sphere, &mv, shell_shading, |frag| {
+                        let p_world = shell_model * glm::vec4(frag.p.x, frag.p.y, frag.p.z, 1.0);
+                        let n_world = glm::normalize(&(normal_to_world * frag.n));
+                        let sun = SunLight {
+                            dir: sun_dir,
+                            visibility: shadow_map
+                                .visibility(glm::vec3(p_world.x, p_world.y, p_world.z), n_world),
+                        };
+                        shade_shell(&layer, frag, &sun, ray_view, sun_view, time_acc)
+                    });
+                    draw_shell(&mut fb, &pm, &surface, &mut prof);
+                }
             }
+            draw_rings(&mut fb, &mut prof, RingHalf::InFront);
+        }
+
+        // ----- Destello de lente: más fuerte cuanto más de frente se mira al Sol -----
+        // Como la corona, solo si el modo de render del Sol lo muestra sombreado
+        let sun_shown = instances.iter().any(shows_corona);
+        if let Some(sun) = sun_screen.as_ref().filter(|_| sun_shown) {
+            let t_glare = prof.start();
+            let to_sun = glm::normalize(&(sun_center_world - cam.pos));
+            let facing = glm::dot(&cam.forward(), &to_sun);
+            let strength = sun.flare_strength(&fb, facing);
+            draw_lens_flare(&mut fb, sun, strength, &shader_params.star_flare);
+            prof.record("corona", t_glare);
         }
 
         // ----- Presentación (skybox + estrellas + HUD) -----
-        let tex = rl
-            .load_texture_from_image(&thread, &fb.color_buffer)
-            .expect("No pude crear Texture2D desde el framebuffer");
+        // Solo se recrea la textura si cambió la resolución interna
+        let t_upload = prof.start();
+        let needs_new_tex = screen_tex
+            .as_ref()
+            .is_none_or(|t| t.width != fb.width || t.height != fb.height);
+        if needs_new_tex {
+            screen_tex = Some(
+                rl.load_texture_from_image(&thread, &fb.to_image())
+                    .expect("No pude crear Texture2D desde el framebuffer"),
+            );
+        }
+        let tex = screen_tex.as_mut().expect("textura de presentación creada arriba");
+        if let Err(e) = tex.update_texture(fb.pixels()) {
+            eprintln!("Error subiendo framebuffer: {e}");
+        }
+        prof.record("subida", t_upload);
+        if let Err(e) = prof.end_frame(&fb.stats) {
+            eprintln!("Error escribiendo el CSV de perfil ({e}); se detiene la grabación");
+        }
 
         let mut d = rl.begin_drawing(&thread);
         d.clear_background(Color::new(4, 8, 20, 255));
-        d.draw_texture(&tex, 0, 0, Color::WHITE);
-
-        // Estrellas 2D estilo skybox
-        for i in 0..STAR_COUNT {
-            let x = ((i * 73 + 19 * i * i) % (WIDTH as usize)) as i32;
-            let y = ((i * 151 + 37) % (HEIGHT as usize)) as i32;
-            let b = 160 + ((i * 97) % 80) as u8;
-            d.draw_pixel(x, y, Color::new(b, b, b, 255));
-        }
+
+        // Escala el framebuffer interno al tamaño real de la ventana
+        d.draw_texture_pro(
+            &*tex,
+            Rectangle::new(0.0, 0.0, fb.width as f32, fb.height as f32),
+            Rectangle::new(0.0, 0.0, screen_w as f32, screen_h as f32),
+            Vector2::new(0.0, 0.0),
+            0.0,
+            Color::WHITE,
+        );
 
         d.draw_text(
-            "WASD/QE mover | Flechas/Mouse mirar | Z/X FOV | M mouse | 1-6 warps | P PNG",
+            "WASD/QE mover | Flechas/Mouse mirar | Z/X FOV | M mouse | 1-6 warps | P PNG | R escala | F11 pantalla | F3 stats | F4 CSV | Tab/V modo | G sombreado | C constelaciones",
+            10,
             10,
+            16,
+            Color::RAYWHITE,
+        );
+        d.draw_text(
+            &format!(
+                "Render {}x{} ({:.0}%) | sombreado {} (G)",
+                fb.width,
+                fb.height,
+                render_scale * 100.0,
+                shading.label()
+            ),
             10,
+            30,
             16,
             Color::RAYWHITE,
         );
 
+        let mode_text = match mode_sel.checked_sub(1) {
+            None => format!("Modo global: {} (Tab objeto, V cambiar)", render_modes.global.label()),
+            Some(target) => format!(
+                "Modo {}: {} (Tab objeto, V cambiar)",
+                mode_targets[target],
+                render_modes
+                    .override_for(target)
+                    .map_or("sigue al global", |m| m.label())
+            ),
+        };
+        d.draw_text(&mode_text, 440, 30, 16, Color::RAYWHITE);
+
+        // Overlay del perfilador (tiempos por pase + contadores)
+        if show_stats {
+            let lines = prof.overlay_lines(&fb.stats);
+            let box_h = 10 + lines.len() as i32 * 18;
+            d.draw_rectangle(6, 50, 430, box_h, Color::new(0, 0, 0, 170));
+            for (i, line) in lines.iter().enumerate() {
+                d.draw_text(line, 12, 56 + i as i32 * 18, 16, Color::LIME);
+            }
+        }
+
+        // Recarga en caliente: errores del archivo (se sigue con la última versión
+        // válida) o aviso breve de recarga correcta
+        let mut hud_y = screen_h - 26;
+        for error in [&shader_file.error, &scene_file.error, &sky_file.error]
+            .into_iter()
+            .flatten()
+        {
+            d.draw_rectangle(6, hud_y - 4, screen_w - 12, 24, Color::new(0, 0, 0, 190));
+            d.draw_text(error, 12, hud_y, 16, Color::RED);
+            hud_y -= 26;
+        }
+        if let Some((path, secs)) = &mut reload_notice {
+            d.draw_text(&format!("Recargado {path}"), 12, hud_y, 16, Color::LIME);
+            *secs -= dt;
+            if *secs <= 0.0 {
+                reload_notice = None;
+            }
+        }
+
         if d.is_key_pressed(KeyboardKey::KEY_P) {
             if let Err(e) = fb.render_to_file("render.png") {
                 eprintln!("Error guardando PNG: {e}");
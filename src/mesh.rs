@@ -0,0 +1,129 @@
+use nalgebra_glm as glm;
+use std::collections::HashMap;
+
+use crate::geom::Vec3;
+use crate::obj::{Face, load_obj};
+
+// Ángulo máximo entre caras vecinas para promediar su normal (si el OBJ no trae `vn`)
+pub const SMOOTH_ANGLE_DEG: f32 = 60.0;
+
+// Malla cargada desde OBJ + datos derivados que usan todos los pases
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    pub faces: Vec<Face>,
+    // arista (i<j) -> caras que la comparten; sirve para contornos y wireframe
+    pub edge_to_faces: HashMap<(usize, usize), Vec<usize>>,
+    // normal geométrica por cara y normal suavizada por esquina (espacio modelo, unitarias)
+    pub face_normals: Vec<Vec3>,
+    pub corner_normals: Vec<[Vec3; 3]>,
+    pub center: Vec3,
+    pub size: Vec3,
+}
+
+impl Mesh {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let (vertices, normals, faces) = load_obj(path)?;
+        Ok(Self::from_parts(vertices, &normals, faces))
+    }
+
+    // Usa las normales `vn` del OBJ si todas las caras las traen; si no, las genera
+    pub fn from_parts(vertices: Vec<Vec3>, normals: &[Vec3], faces: Vec<Face>) -> Self {
+        let (mut v_min, mut v_max) = (
+            glm::vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            glm::vec3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        );
+        for v in &vertices {
+            v_min = glm::min2(&v_min, v);
+            v_max = glm::max2(&v_max, v);
+        }
+
+        let mut edge_to_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (fi, f) in faces.iter().enumerate() {
+            let [i0, i1, i2] = f.vertex_indices;
+            for &(a, b) in &[(i0, i1), (i1, i2), (i2, i0)] {
+                let key = if a < b { (a, b) } else { (b, a) };
+                edge_to_faces.entry(key).or_default().push(fi);
+            }
+        }
+
+        let face_normals: Vec<Vec3> = faces
+            .iter()
+            .map(|f| safe_normalize(face_cross(&vertices, f)))
+            .collect();
+
+        let corner_normals = if faces.iter().all(|f| f.normal_indices.is_some()) {
+            faces
+                .iter()
+                .map(|f| {
+                    let ns = f.normal_indices.expect("comprobado arriba");
+                    ns.map(|i| safe_normalize(normals[i]))
+                })
+                .collect()
+        } else {
+            smooth_normals(&vertices, &faces, SMOOTH_ANGLE_DEG.to_radians())
+        };
+
+        Mesh {
+            vertices,
+            faces,
+            edge_to_faces,
+            face_normals,
+            corner_normals,
+            center: (v_min + v_max) * 0.5,
+            size: v_max - v_min,
+        }
+    }
+
+    // Radio de la esfera que envuelve la caja del modelo
+    pub fn bounding_radius(&self) -> f32 {
+        0.5 * self.size.magnitude()
+    }
+}
+
+// Producto cruz sin normalizar: su módulo es el doble del área de la cara
+fn face_cross(vertices: &[Vec3], f: &Face) -> Vec3 {
+    let [i0, i1, i2] = f.vertex_indices;
+    let (a, b, c) = (vertices[i0], vertices[i1], vertices[i2]);
+    glm::cross(&(b - a), &(c - a))
+}
+
+fn safe_normalize(v: Vec3) -> Vec3 {
+    let len = v.magnitude();
+    if len > 1e-12 { v / len } else { glm::vec3(0.0, 1.0, 0.0) }
+}
+
+// Normales por esquina promediando (ponderado por área) las caras que comparten
+// la posición del vértice y cuyo ángulo con la cara propia no supera `max_angle`.
+// Por encima del umbral la arista queda dura. Se agrupa por posición para que
+// las costuras con vértices duplicados también queden suaves.
+pub fn smooth_normals(vertices: &[Vec3], faces: &[Face], max_angle: f32) -> Vec<[Vec3; 3]> {
+    let key = |v: &Vec3| {
+        let q = |x: f32| (x * 1e4).round() as i64;
+        (q(v.x), q(v.y), q(v.z))
+    };
+
+    let crosses: Vec<Vec3> = faces.iter().map(|f| face_cross(vertices, f)).collect();
+    let units: Vec<Vec3> = crosses.iter().map(|c| safe_normalize(*c)).collect();
+
+    let mut faces_at: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    for (fi, f) in faces.iter().enumerate() {
+        for &vi in &f.vertex_indices {
+            faces_at.entry(key(&vertices[vi])).or_default().push(fi);
+        }
+    }
+
+    let cos_max = max_angle.cos();
+    faces
+        .iter()
+        .enumerate()
+        .map(|(fi, f)| {
+            f.vertex_indices.map(|vi| {
+                let sum = faces_at[&key(&vertices[vi])]
+                    .iter()
+                    .filter(|&&other| glm::dot(&units[fi], &units[other]) >= cos_max)
+                    .fold(glm::vec3(0.0, 0.0, 0.0), |acc, &other| acc + crosses[other]);
+                safe_normalize(sum)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,301 @@
+use nalgebra_glm as glm;
+
+use crate::geom::Vec3;
+
+// Ruido 3D con semilla. No usa tablas de permutación: cada punto de la rejilla
+// se obtiene con un hash de (x, y, z, semilla), así que un `Noise` es Copy y
+// crear uno por cuerpo no cuesta nada. Pensado para evaluarse sobre la posición
+// en la esfera unidad (sin costuras en longitud ni pellizcos en los polos).
+#[derive(Copy, Clone, Debug)]
+pub struct Noise {
+    seed: u32,
+}
+
+// Parámetros de los fractales (fBm, turbulencia, ridged)
+#[derive(Copy, Clone, Debug)]
+pub struct Fractal {
+    pub octaves: u32,
+    // factor de frecuencia entre octavas
+    pub lacunarity: f32,
+    // factor de amplitud entre octavas
+    pub gain: f32,
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl Fractal {
+    pub fn octaves(octaves: u32) -> Self {
+        Self {
+            octaves,
+            ..Self::default()
+        }
+    }
+}
+
+// 12 gradientes de las aristas de un cubo (Perlin mejorado)
+const GRAD3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+#[inline]
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[inline]
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+impl Noise {
+    pub const fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+
+    // Otro generador independiente derivado de este (para canales distintos)
+    pub fn derive(self, salt: u32) -> Self {
+        Self::new(self.seed ^ salt.wrapping_mul(0x9e37_79b9))
+    }
+
+    // Número aleatorio en [0, 1) para el índice `i` (parámetros sacados de una semilla)
+    pub fn random(&self, i: u32) -> f32 {
+        self.unit(i as i32, 0, 0, 4)
+    }
+
+    #[inline]
+    fn hash(&self, x: i32, y: i32, z: i32, salt: u32) -> u32 {
+        let mut h = self.seed.wrapping_mul(0x27d4_eb2d)
+            ^ (x as u32).wrapping_mul(0x8da6_b343)
+            ^ (y as u32).wrapping_mul(0xd816_3841)
+            ^ (z as u32).wrapping_mul(0xcb1a_b31f)
+            ^ salt.wrapping_mul(0x68e3_1da4);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x2c1b_3c6d);
+        h ^= h >> 12;
+        h = h.wrapping_mul(0x297a_2d39);
+        h ^= h >> 15;
+        h
+    }
+
+    // hash -> [0, 1)
+    #[inline]
+    fn unit(&self, x: i32, y: i32, z: i32, salt: u32) -> f32 {
+        (self.hash(x, y, z, salt) >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    #[inline]
+    fn grad(&self, x: i32, y: i32, z: i32, dx: f32, dy: f32, dz: f32) -> f32 {
+        let g = GRAD3[(self.hash(x, y, z, 0) % 12) as usize];
+        g[0] * dx + g[1] * dy + g[2] * dz
+    }
+
+    // Perlin clásico (mejorado), aprox. en [-1, 1]
+    pub fn perlin(&self, p: Vec3) -> f32 {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (x, y, z) = (fx as i32, fy as i32, fz as i32);
+        let (dx, dy, dz) = (p.x - fx, p.y - fy, p.z - fz);
+        let (u, v, w) = (fade(dx), fade(dy), fade(dz));
+
+        let n000 = self.grad(x, y, z, dx, dy, dz);
+        let n100 = self.grad(x + 1, y, z, dx - 1.0, dy, dz);
+        let n010 = self.grad(x, y + 1, z, dx, dy - 1.0, dz);
+        let n110 = self.grad(x + 1, y + 1, z, dx - 1.0, dy - 1.0, dz);
+        let n001 = self.grad(x, y, z + 1, dx, dy, dz - 1.0);
+        let n101 = self.grad(x + 1, y, z + 1, dx - 1.0, dy, dz - 1.0);
+        let n011 = self.grad(x, y + 1, z + 1, dx, dy - 1.0, dz - 1.0);
+        let n111 = self.grad(x + 1, y + 1, z + 1, dx - 1.0, dy - 1.0, dz - 1.0);
+
+        let x00 = mix(n000, n100, u);
+        let x10 = mix(n010, n110, u);
+        let x01 = mix(n001, n101, u);
+        let x11 = mix(n011, n111, u);
+        mix(mix(x00, x10, v), mix(x01, x11, v), w)
+    }
+
+    // Simplex 3D, aprox. en [-1, 1]; menos artefactos de rejilla que Perlin
+    pub fn simplex(&self, p: Vec3) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+
+        let s = (p.x + p.y + p.z) * F3;
+        let (i, j, k) = (
+            (p.x + s).floor() as i32,
+            (p.y + s).floor() as i32,
+            (p.z + s).floor() as i32,
+        );
+        let t = (i + j + k) as f32 * G3;
+        let x0 = p.x - (i as f32 - t);
+        let y0 = p.y - (j as f32 - t);
+        let z0 = p.z - (k as f32 - t);
+
+        // Qué tetraedro del cubo contiene al punto
+        let (i1, j1, k1, i2, j2, k2) = if x0 >= y0 {
+            if y0 >= z0 {
+                (1, 0, 0, 1, 1, 0)
+            } else if x0 >= z0 {
+                (1, 0, 0, 1, 0, 1)
+            } else {
+                (0, 0, 1, 1, 0, 1)
+            }
+        } else if y0 < z0 {
+            (0, 0, 1, 0, 1, 1)
+        } else if x0 < z0 {
+            (0, 1, 0, 0, 1, 1)
+        } else {
+            (0, 1, 0, 1, 1, 0)
+        };
+
+        let corners = [
+            (0, 0, 0, x0, y0, z0),
+            (
+                i1,
+                j1,
+                k1,
+                x0 - i1 as f32 + G3,
+                y0 - j1 as f32 + G3,
+                z0 - k1 as f32 + G3,
+            ),
+            (
+                i2,
+                j2,
+                k2,
+                x0 - i2 as f32 + 2.0 * G3,
+                y0 - j2 as f32 + 2.0 * G3,
+                z0 - k2 as f32 + 2.0 * G3,
+            ),
+            (
+                1,
+                1,
+                1,
+                x0 - 1.0 + 3.0 * G3,
+                y0 - 1.0 + 3.0 * G3,
+                z0 - 1.0 + 3.0 * G3,
+            ),
+        ];
+
+        let mut n = 0.0;
+        for (ci, cj, ck, dx, dy, dz) in corners {
+            let t = 0.6 - dx * dx - dy * dy - dz * dz;
+            if t > 0.0 {
+                let t2 = t * t;
+                n += t2 * t2 * self.grad(i + ci, j + cj, k + ck, dx, dy, dz);
+            }
+        }
+        32.0 * n
+    }
+
+    // Ruido de valor: valores aleatorios en la rejilla interpolados, en [-1, 1]
+    pub fn value(&self, p: Vec3) -> f32 {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (x, y, z) = (fx as i32, fy as i32, fz as i32);
+        let (u, v, w) = (fade(p.x - fx), fade(p.y - fy), fade(p.z - fz));
+        let at = |dx: i32, dy: i32, dz: i32| self.unit(x + dx, y + dy, z + dz, 0) * 2.0 - 1.0;
+
+        let x00 = mix(at(0, 0, 0), at(1, 0, 0), u);
+        let x10 = mix(at(0, 1, 0), at(1, 1, 0), u);
+        let x01 = mix(at(0, 0, 1), at(1, 0, 1), u);
+        let x11 = mix(at(0, 1, 1), at(1, 1, 1), u);
+        mix(mix(x00, x10, v), mix(x01, x11, v), w)
+    }
+
+    // Worley / celular: distancias al punto característico más cercano (f1) y al
+    // segundo (f2). Un punto aleatorio por celda; f1 ~ [0, 1], f2 >= f1.
+    pub fn worley(&self, p: Vec3) -> (f32, f32) {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (x, y, z) = (fx as i32, fy as i32, fz as i32);
+        let (mut f1, mut f2) = (f32::MAX, f32::MAX);
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (cx, cy, cz) = (x + dx, y + dy, z + dz);
+                    let feature = glm::vec3(
+                        cx as f32 + self.unit(cx, cy, cz, 1),
+                        cy as f32 + self.unit(cx, cy, cz, 2),
+                        cz as f32 + self.unit(cx, cy, cz, 3),
+                    );
+                    let d = glm::distance2(&p, &feature);
+                    if d < f1 {
+                        f2 = f1;
+                        f1 = d;
+                    } else if d < f2 {
+                        f2 = d;
+                    }
+                }
+            }
+        }
+        (f1.sqrt(), f2.sqrt())
+    }
+}
+
+//
+// ----- COMBINADORES -----
+//
+// Reciben la base como closure (p. ej. `|q| noise.simplex(q)`), así sirven para
+// cualquier tipo de ruido.
+
+// Movimiento browniano fraccional: suma de octavas, normalizada a ~[-1, 1]
+pub fn fbm(p: Vec3, fractal: &Fractal, basis: impl Fn(Vec3) -> f32) -> f32 {
+    let (mut sum, mut amp, mut freq, mut norm) = (0.0, 1.0, 1.0, 0.0);
+    for octave in 0..fractal.octaves {
+        // desplazamiento por octava para que los ceros de la rejilla no se alineen
+        let offset = octave as f32 * 17.31;
+        sum += amp * basis(p * freq + glm::vec3(offset, -offset, offset * 0.5));
+        norm += amp;
+        amp *= fractal.gain;
+        freq *= fractal.lacunarity;
+    }
+    sum / norm.max(1e-6)
+}
+
+// Turbulencia: fBm del valor absoluto, en [0, 1]; da pliegues marcados
+pub fn turbulence(p: Vec3, fractal: &Fractal, basis: impl Fn(Vec3) -> f32) -> f32 {
+    fbm(p, fractal, |q| basis(q).abs())
+}
+
+// Ridged multifractal (Musgrave): crestas finas donde la base cruza por cero;
+// cada octava pesa según la anterior. En [0, 1].
+pub fn ridged(p: Vec3, fractal: &Fractal, basis: impl Fn(Vec3) -> f32) -> f32 {
+    let offset = 1.0;
+    let (mut sum, mut amp, mut freq, mut norm, mut weight) = (0.0, 1.0, 1.0, 0.0, 1.0);
+    for octave in 0..fractal.octaves {
+        let shift = octave as f32 * 17.31;
+        let mut signal = offset - basis(p * freq + glm::vec3(shift, -shift, shift * 0.5)).abs();
+        signal = signal * signal * weight;
+        weight = (signal * 2.0).clamp(0.0, 1.0);
+        sum += signal * amp;
+        norm += amp;
+        amp *= fractal.gain;
+        freq *= fractal.lacunarity;
+    }
+    (sum / norm.max(1e-6)).clamp(0.0, 1.0)
+}
+
+// Deformación del dominio: desplaza `p` con tres evaluaciones desfasadas de la
+// base. El resultado se usa como nueva posición para otro ruido.
+pub fn domain_warp(p: Vec3, strength: f32, basis: impl Fn(Vec3) -> f32) -> Vec3 {
+    let w = glm::vec3(
+        basis(p),
+        basis(p + glm::vec3(5.2, 1.3, -2.8)),
+        basis(p + glm::vec3(-1.7, 9.2, 3.4)),
+    );
+    p + w * strength
+}
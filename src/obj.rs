@@ -6,50 +6,71 @@ use crate::geom::Vec3;
 #[derive(Debug, Clone)]
 pub struct Face {
     pub vertex_indices: [usize; 3],
+    // índices `vn` por esquina; None si la cara no trae normales
+    pub normal_indices: Option<[usize; 3]>,
 }
 
-pub fn load_obj(path: &str) -> Result<(Vec<Vec3>, Vec<Face>)> {
+pub fn load_obj(path: &str) -> Result<(Vec<Vec3>, Vec<Vec3>, Vec<Face>)> {
     let text = std::fs::read_to_string(path)?;
     let mut vertices: Vec<Vec3> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
     let mut faces: Vec<Face> = Vec::new();
 
     for (lineno, line) in text.lines().enumerate() {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') { continue; }
 
-        if line.starts_with("v ") {
-            let parts: Vec<_> = line[2..].split_whitespace().collect();
+        if let Some(rest) = line.strip_prefix("v ") {
+            let parts: Vec<_> = rest.split_whitespace().collect();
             if parts.len() < 3 { return Err(anyhow!("Línea v inválida @{}: {}", lineno+1, line)); }
             let x: f32 = parts[0].parse()?;
             let y: f32 = parts[1].parse()?;
             let z: f32 = parts[2].parse()?;
             vertices.push(glm::vec3(x, y, z));
-        } else if line.starts_with("f ") {
-            let toks: Vec<_> = line[2..].split_whitespace().collect();
+        } else if let Some(rest) = line.strip_prefix("vn ") {
+            let parts: Vec<_> = rest.split_whitespace().collect();
+            if parts.len() < 3 { return Err(anyhow!("Línea vn inválida @{}: {}", lineno+1, line)); }
+            let x: f32 = parts[0].parse()?;
+            let y: f32 = parts[1].parse()?;
+            let z: f32 = parts[2].parse()?;
+            normals.push(glm::vec3(x, y, z));
+        } else if let Some(rest) = line.strip_prefix("f ") {
+            let toks: Vec<_> = rest.split_whitespace().collect();
             if toks.len() < 3 { return Err(anyhow!("Cara con <3 vértices @{}", lineno+1)); }
 
-            let idx_from_token = |tok: &str| -> Result<usize> {
-                let first = tok.split('/').next().ok_or_else(|| anyhow!("Token f inválido"))?;
-                let i: i32 = first.parse()?;
-                let idx = if i > 0 { (i - 1) as usize } else {
-                    let len = vertices.len() as i32;
-                    (len + i) as usize
-                };
+            // índice OBJ (1-based o negativo relativo) -> índice 0-based
+            let resolve = |field: &str, len: usize| -> Result<usize> {
+                let i: i32 = field.parse()?;
+                let idx = if i > 0 { (i - 1) as usize } else { (len as i32 + i) as usize };
                 Ok(idx)
             };
 
+            // v, v/vt, v//vn o v/vt/vn
+            let idx_from_token = |tok: &str| -> Result<(usize, Option<usize>)> {
+                let mut fields = tok.split('/');
+                let first = fields.next().ok_or_else(|| anyhow!("Token f inválido"))?;
+                let v = resolve(first, vertices.len())?;
+                let n = match fields.nth(1) {
+                    Some(f) if !f.is_empty() => Some(resolve(f, normals.len())?),
+                    _ => None,
+                };
+                Ok((v, n))
+            };
+
             let indices: Result<Vec<_>> = toks.iter().map(|t| idx_from_token(t)).collect();
             let indices = indices?;
 
-            if indices.len() == 3 {
-                faces.push(Face { vertex_indices: [indices[0], indices[1], indices[2]] });
-            } else if indices.len() == 4 {
-                faces.push(Face { vertex_indices: [indices[0], indices[1], indices[2]] });
-                faces.push(Face { vertex_indices: [indices[0], indices[2], indices[3]] });
-            } else {
-                for i in 1..indices.len()-1 {
-                    faces.push(Face { vertex_indices: [indices[0], indices[i], indices[i+1]] });
-                }
+            // triangulación en abanico (quads y polígonos convexos)
+            for i in 1..indices.len()-1 {
+                let corners = [indices[0], indices[i], indices[i+1]];
+                let normal_indices = match corners {
+                    [(_, Some(n0)), (_, Some(n1)), (_, Some(n2))] => Some([n0, n1, n2]),
+                    _ => None,
+                };
+                faces.push(Face {
+                    vertex_indices: [corners[0].0, corners[1].0, corners[2].0],
+                    normal_indices,
+                });
             }
         }
     }
@@ -57,5 +78,11 @@ pub fn load_obj(path: &str) -> Result<(Vec<Vec3>, Vec<Face>)> {
     if vertices.is_empty() { return Err(anyhow!("Sin vértices en {}", path)); }
     if faces.is_empty() { return Err(anyhow!("Sin caras en {}", path)); }
 
-    Ok((vertices, faces))
+    for f in &faces {
+        let out_of_range = f.vertex_indices.iter().any(|&i| i >= vertices.len())
+            || f.normal_indices.is_some_and(|ns| ns.iter().any(|&i| i >= normals.len()));
+        if out_of_range { return Err(anyhow!("Índice fuera de rango en {}", path)); }
+    }
+
+    Ok((vertices, normals, faces))
 }
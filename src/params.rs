@@ -0,0 +1,339 @@
+use anyhow::{anyhow, Result};
+use nalgebra_glm as glm;
+
+// Parámetros editables en archivos de texto tipo INI:
+//
+//   # comentario
+//   [seccion]
+//   clave = 0.5
+//   color = 0.9, 0.8, 0.3
+//
+// Cada sección es un struct definido con `params_struct!`; las claves que no
+// aparecen conservan su valor por defecto. `section_text` escribe una sección en
+// el mismo formato.
+
+// Tipo de un campo: cuántos números ocupa en el archivo
+pub trait ParamValue: Sized {
+    const EXPECTED: &'static str;
+    fn from_values(values: &[f32]) -> Option<Self>;
+    // Valor como se escribe en el archivo (sin perder precisión)
+    fn to_text(&self) -> String;
+}
+
+impl ParamValue for f32 {
+    const EXPECTED: &'static str = "un número";
+
+    fn from_values(values: &[f32]) -> Option<Self> {
+        match values {
+            [v] => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl ParamValue for glm::Vec3 {
+    const EXPECTED: &'static str = "tres números (r, g, b o x, y, z)";
+
+    fn from_values(values: &[f32]) -> Option<Self> {
+        match values {
+            [x, y, z] => Some(glm::vec3(*x, *y, *z)),
+            _ => None,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        format!("{:?}, {:?}, {:?}", self.x, self.y, self.z)
+    }
+}
+
+// Una sección: asigna sus campos por nombre
+pub trait ParamSection {
+    fn set(&mut self, key: &str, values: &[f32]) -> Result<()>;
+    // Una línea `clave = valor` por campo, en el orden del struct
+    fn write(&self, out: &mut String);
+}
+
+// Conjunto de secciones que se puede leer de un archivo
+pub trait ParamSections {
+    fn section_mut(&mut self, name: &str) -> Option<&mut dyn ParamSection>;
+}
+
+// Define un struct de parámetros (Copy) con sus valores por defecto y su
+// asignación por nombre. Los campos deben ser f32 o Vec3.
+macro_rules! params_struct {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $($field:ident: $ty:ty = $default:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug)]
+        pub struct $name {
+            $(pub $field: $ty,)*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl $crate::params::ParamSection for $name {
+            fn set(&mut self, key: &str, values: &[f32]) -> anyhow::Result<()> {
+                use $crate::params::ParamValue;
+                $(
+                    if key == stringify!($field) {
+                        self.$field = <$ty>::from_values(values).ok_or_else(|| {
+                            anyhow::anyhow!("'{}' espera {}", key, <$ty>::EXPECTED)
+                        })?;
+                        return Ok(());
+                    }
+                )*
+                Err(anyhow::anyhow!("clave desconocida '{}'", key))
+            }
+
+            fn write(&self, out: &mut String) {
+                use $crate::params::ParamValue;
+                $(
+                    out.push_str(&format!("{} = {}\n", stringify!($field), self.$field.to_text()));
+                )*
+            }
+        }
+    };
+}
+pub(crate) use params_struct;
+
+// Línea útil de un archivo de configuración (sin comentarios ni líneas vacías)
+pub enum ConfigLine<'a> {
+    Section(&'a str),
+    Entry(&'a str, &'a str),
+}
+
+// Error con archivo y línea (numerada desde 1)
+pub fn line_error(origin: &str, lineno: usize, msg: impl std::fmt::Display) -> anyhow::Error {
+    anyhow!("{}:{}: {}", origin, lineno, msg)
+}
+
+// Separa el texto en secciones `[nombre]` y entradas `clave = valor`, cada una con
+// su número de línea. Compartido por los parámetros de shaders y la escena.
+pub fn config_lines<'a>(text: &'a str, origin: &str) -> Result<Vec<(usize, ConfigLine<'a>)>> {
+    let mut lines = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let lineno = i + 1;
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| line_error(origin, lineno, format!("sección sin cerrar: {line}")))?;
+            lines.push((lineno, ConfigLine::Section(name.trim())));
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            line_error(origin, lineno, format!("se esperaba 'clave = valor': {line}"))
+        })?;
+        lines.push((lineno, ConfigLine::Entry(key.trim(), value.trim())));
+    }
+    Ok(lines)
+}
+
+// "0.5" o "0.9, 0.8, 0.3" -> números
+pub fn parse_numbers(value: &str) -> Option<Vec<f32>> {
+    value.split(',').map(|v| v.trim().parse::<f32>().ok()).collect()
+}
+
+// Texto de la sección `name` con los valores actuales; `apply` lo vuelve a leer igual
+pub fn section_text(name: &str, section: &dyn ParamSection) -> String {
+    let mut text = format!("[{name}]\n");
+    section.write(&mut text);
+    text
+}
+
+// Aplica el texto de un archivo de parámetros sobre `target`. `origin` (nombre del
+// archivo) solo se usa en los mensajes de error, que indican la línea.
+pub fn apply(target: &mut dyn ParamSections, text: &str, origin: &str) -> Result<()> {
+    let mut section: Option<&str> = None;
+    for (lineno, line) in config_lines(text, origin)? {
+        let err = |msg: String| line_error(origin, lineno, msg);
+        match line {
+            ConfigLine::Section(name) => {
+                if target.section_mut(name).is_none() {
+                    return Err(err(format!("sección desconocida [{name}]")));
+                }
+                section = Some(name);
+            }
+            ConfigLine::Entry(key, value) => {
+                let name = section.ok_or_else(|| err("clave fuera de una [sección]".to_string()))?;
+                let values = parse_numbers(value)
+                    .ok_or_else(|| err(format!("valor no numérico: {value}")))?;
+                let sec = target
+                    .section_mut(name)
+                    .ok_or_else(|| err(format!("sección desconocida [{name}]")))?;
+                sec.set(key, &values).map_err(|e| err(e.to_string()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    params_struct! {
+        #[derive(PartialEq)]
+        pub struct LampParams {
+            power: f32 = 1.0,
+            color: glm::Vec3 = glm::vec3(1.0, 0.5, 0.25),
+        }
+    }
+
+    params_struct! {
+        #[derive(PartialEq)]
+        pub struct FogParams {
+            density: f32 = 0.1,
+        }
+    }
+
+    #[derive(Default)]
+    struct Room {
+        lamp: LampParams,
+        fog: FogParams,
+    }
+
+    impl ParamSections for Room {
+        fn section_mut(&mut self, name: &str) -> Option<&mut dyn ParamSection> {
+            Some(match name {
+                "lamp" => &mut self.lamp,
+                "fog" => &mut self.fog,
+                _ => return None,
+            })
+        }
+    }
+
+    fn room(text: &str) -> Room {
+        let mut room = Room::default();
+        apply(&mut room, text, "test.cfg").unwrap_or_else(|e| panic!("{e}"));
+        room
+    }
+
+    fn error(text: &str) -> String {
+        match apply(&mut Room::default(), text, "test.cfg") {
+            Ok(()) => panic!("se aceptó:\n{text}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn omitted_keys_keep_defaults() {
+        let r = room("# solo la potencia\n[lamp]\npower = 2.5  # comentario\n");
+        assert_eq!(r.lamp.power, 2.5);
+        assert_eq!(r.lamp.color, LampParams::default().color);
+        assert_eq!(r.fog, FogParams::default());
+
+        let r = room("");
+        assert_eq!(r.lamp, LampParams::default());
+    }
+
+    #[test]
+    fn sections_switch() {
+        let r = room("[lamp]\npower = 2\n\n[fog]\ndensity = 0.5\n[ lamp ]\ncolor = 0, 0 ,1\n");
+        assert_eq!(r.lamp.power, 2.0);
+        assert_eq!(r.lamp.color, glm::vec3(0.0, 0.0, 1.0));
+        assert_eq!(r.fog.density, 0.5);
+
+        // cada clave va a la sección abierta
+        assert_eq!(
+            error("[fog]\npower = 2\n"),
+            "test.cfg:2: clave desconocida 'power'"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_and_sections() {
+        assert_eq!(
+            error("[lamp]\npower = 2\nbrightness = 3\n"),
+            "test.cfg:3: clave desconocida 'brightness'"
+        );
+        assert_eq!(
+            error("[lamp]\n\n[attic]\npower = 2\n"),
+            "test.cfg:3: sección desconocida [attic]"
+        );
+        assert_eq!(
+            error("power = 2\n"),
+            "test.cfg:1: clave fuera de una [sección]"
+        );
+        assert_eq!(error("[lamp\n"), "test.cfg:1: sección sin cerrar: [lamp");
+    }
+
+    #[test]
+    fn malformed_values() {
+        assert_eq!(
+            error("[lamp]\ncolor = 1, 0.5\n"),
+            "test.cfg:2: 'color' espera tres números (r, g, b o x, y, z)"
+        );
+        assert_eq!(
+            error("[lamp]\n# uno\n\npower = 1, 2\n"),
+            "test.cfg:4: 'power' espera un número"
+        );
+        assert_eq!(
+            error("[lamp]\ncolor = 1, x, 0\n"),
+            "test.cfg:2: valor no numérico: 1, x, 0"
+        );
+        assert_eq!(
+            error("[fog]\ndensity =\n"),
+            "test.cfg:2: valor no numérico: "
+        );
+        assert_eq!(
+            error("[fog]\ndensity 0.5\n"),
+            "test.cfg:2: se esperaba 'clave = valor': density 0.5"
+        );
+    }
+
+    #[test]
+    fn written_sections_read_back() {
+        let mut written = Room::default();
+        written.lamp.power = 1.0 / 3.0;
+        written.lamp.color = glm::vec3(0.1, -2.5, 1e-7);
+        written.fog.density = 12345.678;
+        let text = section_text("lamp", &written.lamp) + &section_text("fog", &written.fog);
+
+        let lines = config_lines(&text, "test.cfg").unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(lines.len(), 2 + 3);
+        let r = room(&text);
+        assert_eq!(r.lamp, written.lamp);
+        assert_eq!(r.fog, written.fog);
+    }
+
+    // assets/shaders.cfg dice que sus valores son los por defecto
+    #[test]
+    fn shipped_shader_file_has_the_defaults() {
+        use crate::shaders::ShaderParams;
+
+        let path = "assets/shaders.cfg";
+        let mut file = ShaderParams::load(path).unwrap_or_else(|e| panic!("{e}"));
+        let mut defaults = ShaderParams::default();
+        let text = std::fs::read_to_string(path).unwrap();
+        let mut sections = 0;
+        for (_, line) in config_lines(&text, path).unwrap() {
+            if let ConfigLine::Section(name) = line {
+                let expected = section_text(name, defaults.section_mut(name).unwrap());
+                let read = section_text(name, file.section_mut(name).unwrap());
+                assert_eq!(read, expected);
+                sections += 1;
+            }
+        }
+        assert!(sections > 0);
+    }
+}
@@ -0,0 +1,171 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+// Contadores del rasterizador; el FrameBuffer los acumula y se reinician en clear()
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RenderStats {
+    pub triangles_submitted: u64,
+    pub triangles_culled: u64,
+    pub triangles_rasterized: u64,
+    pub pixels_shaded: u64,
+    pub depth_rejected: u64,
+}
+
+// Tiempos por pase (scopes con nombre) del frame actual + volcado opcional a CSV
+pub struct Profiler {
+    scopes: Vec<(&'static str, f64)>,
+    frame_start: Instant,
+    // trabajo del frame: de begin_frame a end_frame
+    frame_ms: f64,
+    // de un begin_frame al siguiente: incluye presentar y esperar el vsync (da los fps)
+    interval_ms: f64,
+    frame: u64,
+    csv: Option<BufWriter<File>>,
+    csv_columns: Vec<&'static str>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            scopes: Vec::new(),
+            frame_start: Instant::now(),
+            frame_ms: 0.0,
+            interval_ms: 0.0,
+            frame: 0,
+            csv: None,
+            csv_columns: Vec::new(),
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        for (_, ms) in &mut self.scopes {
+            *ms = 0.0;
+        }
+        let now = Instant::now();
+        // el primer frame no tiene anterior
+        if self.frame > 0 {
+            self.interval_ms = (now - self.frame_start).as_secs_f64() * 1000.0;
+        }
+        self.frame_start = now;
+    }
+
+    #[inline]
+    pub fn start(&self) -> Instant {
+        Instant::now()
+    }
+
+    // Acumula el tiempo desde `since` en el scope `name` (puede llamarse varias veces por frame)
+    pub fn record(&mut self, name: &'static str, since: Instant) {
+        let ms = since.elapsed().as_secs_f64() * 1000.0;
+        match self.scopes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, acc)) => *acc += ms,
+            None => self.scopes.push((name, ms)),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.csv.is_some()
+    }
+
+    pub fn start_csv(&mut self, path: &str) -> anyhow::Result<()> {
+        self.csv = Some(BufWriter::new(File::create(path)?));
+        self.csv_columns.clear();
+        Ok(())
+    }
+
+    pub fn stop_csv(&mut self) {
+        if let Some(mut w) = self.csv.take() {
+            let _ = w.flush();
+        }
+    }
+
+    // Cierra el frame: fija su tiempo de trabajo y, si hay CSV activo, escribe una fila.
+    // Si la escritura falla se detiene la grabación y se devuelve el error.
+    pub fn end_frame(&mut self, stats: &RenderStats) -> anyhow::Result<()> {
+        self.frame_ms = self.frame_start.elapsed().as_secs_f64() * 1000.0;
+        self.frame += 1;
+
+        if self.csv.is_none() {
+            return Ok(());
+        }
+        let result = self.write_csv_row(stats);
+        if result.is_err() {
+            self.stop_csv();
+        }
+        result
+    }
+
+    fn write_csv_row(&mut self, stats: &RenderStats) -> anyhow::Result<()> {
+        let Some(csv) = self.csv.as_mut() else {
+            return Ok(());
+        };
+
+        // Las columnas se fijan con los scopes del primer frame grabado
+        if self.csv_columns.is_empty() {
+            self.csv_columns = self.scopes.iter().map(|(n, _)| *n).collect();
+            let mut header = String::from("frame,interval_ms,frame_ms");
+            for name in &self.csv_columns {
+                header.push_str(&format!(",{name}_ms"));
+            }
+            header.push_str(
+                ",tri_submitted,tri_culled,tri_rasterized,pixels_shaded,depth_rejected",
+            );
+            writeln!(csv, "{header}")?;
+        }
+
+        let mut row = format!(
+            "{},{:.4},{:.4}",
+            self.frame, self.interval_ms, self.frame_ms
+        );
+        for name in &self.csv_columns {
+            let ms = self
+                .scopes
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(0.0, |(_, ms)| *ms);
+            row.push_str(&format!(",{ms:.4}"));
+        }
+        row.push_str(&format!(
+            ",{},{},{},{},{}",
+            stats.triangles_submitted,
+            stats.triangles_culled,
+            stats.triangles_rasterized,
+            stats.pixels_shaded,
+            stats.depth_rejected
+        ));
+        writeln!(csv, "{row}")?;
+        Ok(())
+    }
+
+    // Líneas de texto para el overlay en pantalla
+    pub fn overlay_lines(&self, stats: &RenderStats) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Frame {:.2} ms ({:.0} fps) | trabajo {:.2} ms",
+            self.interval_ms,
+            1000.0 / self.interval_ms.max(1e-3),
+            self.frame_ms
+        )];
+        for (name, ms) in &self.scopes {
+            lines.push(format!("  {name:<12} {ms:7.2} ms"));
+        }
+        lines.push(format!(
+            "Triangulos: {} enviados | {} descartados | {} rasterizados",
+            stats.triangles_submitted, stats.triangles_culled, stats.triangles_rasterized
+        ));
+        lines.push(format!(
+            "Pixeles: {} sombreados | {} rechazados por z",
+            stats.pixels_shaded, stats.depth_rejected
+        ));
+        if self.is_recording() {
+            lines.push("Grabando CSV...".to_string());
+        }
+        lines
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use raylib::prelude::Color;
+
+use crate::geom::Vec3;
+use crate::shaders::{Atmosphere, BodyShader, CloudLayer, Fragment, SunLight};
+
+// Shader de superficie definido fuera de `shaders.rs`. Recibe el mismo punto que
+// los shaders del sistema: posición y normal en espacio modelo (`frag`), centro y
+// radio de la esfera base, tiempo de simulación y luz del Sol (con sombras).
+pub trait SurfaceShader {
+    // Nombre para HUD y selección de objetos
+    fn label(&self) -> &'static str;
+
+    fn shade(&self, frag: &Fragment, center: Vec3, radius: f32, time: f32, sun: &SunLight)
+    -> Color;
+
+    // Cascos translúcidos opcionales (se dibujan igual que los de la Tierra)
+    fn atmosphere(&self) -> Option<Atmosphere> {
+        None
+    }
+
+    fn clouds(&self) -> Option<CloudLayer> {
+        None
+    }
+}
+
+// Shaders disponibles por nombre: los del sistema y los que registre quien use la
+// biblioteca. Las escenas eligen el shader de cada cuerpo con este nombre.
+pub struct ShaderRegistry {
+    shaders: BTreeMap<String, BodyShader>,
+}
+
+impl ShaderRegistry {
+    // Registro vacío (sin los shaders del sistema)
+    pub fn empty() -> Self {
+        Self {
+            shaders: BTreeMap::new(),
+        }
+    }
+
+    // Registro con los shaders del sistema (nombres usados en assets/scene.cfg)
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::empty();
+        for (name, kind) in [
+            ("star", BodyShader::Star),
+            ("mercury", BodyShader::Mercury),
+            ("venus", BodyShader::Venus),
+            ("earth", BodyShader::Rocky),
+            ("mars", BodyShader::Mars),
+            ("jupiter", BodyShader::GasGiant),
+            ("moon", BodyShader::Moon),
+            ("io", BodyShader::Volcanic),
+        ] {
+            registry.insert(name, kind);
+        }
+        registry
+    }
+
+    // Añade (o reemplaza) un shader propio. Los cuerpos que lo usan comparten el
+    // mismo Arc; se libera cuando ya no queda ninguno (ni el registro).
+    pub fn register(&mut self, name: &str, shader: impl SurfaceShader + 'static) {
+        self.insert(name, BodyShader::Custom(Arc::new(shader)));
+    }
+
+    // Asocia un nombre a un cuerpo ya existente (p. ej. un planeta procedural fijo)
+    pub fn insert(&mut self, name: &str, kind: BodyShader) {
+        self.shaders.insert(name.to_string(), kind);
+    }
+
+    pub fn get(&self, name: &str) -> Option<BodyShader> {
+        self.shaders.get(name).cloned()
+    }
+
+    // Nombres registrados en orden alfabético (para mensajes de error)
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.shaders.keys().map(String::as_str)
+    }
+}
@@ -0,0 +1,418 @@
+use nalgebra_glm as glm;
+use raylib::prelude::Color;
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::Vec3;
+use crate::line::line_depth_thick;
+use crate::mesh::Mesh;
+use crate::pixel::point;
+use crate::profiler::Profiler;
+use crate::shaders::Fragment;
+use crate::transform::project_vertices_perspective;
+use crate::triangle::{triangle, triangle_filled, triangle_filled_shaded};
+
+// Color de las aristas cuando se superponen a la malla (shaded+wire, hidden-line)
+const WIRE_COLOR: Color = Color::new(120, 230, 255, 255);
+const VERTEX_COLOR: Color = Color::new(255, 230, 90, 255);
+
+// Las aristas caen exactamente sobre su cara; se adelantan un poco en z
+// para que la prueba de profundidad no las mezcle con el relleno
+const EDGE_DEPTH_BIAS: f32 = 2e-6;
+
+// Grosor de contorno para cuerpos sin borde (el Sol, que se funde con su corona)
+pub const NO_OUTLINE: i32 = -1;
+
+//
+// ----- MODOS DE RENDER -----
+//
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RenderMode {
+    Shaded,
+    Wireframe,
+    ShadedWireframe,
+    Vertices,
+    HiddenLine,
+}
+
+impl RenderMode {
+    pub const ALL: [RenderMode; 5] = [
+        RenderMode::Shaded,
+        RenderMode::Wireframe,
+        RenderMode::ShadedWireframe,
+        RenderMode::Vertices,
+        RenderMode::HiddenLine,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RenderMode::Shaded => "relleno",
+            RenderMode::Wireframe => "wireframe",
+            RenderMode::ShadedWireframe => "relleno+wire",
+            RenderMode::Vertices => "vertices",
+            RenderMode::HiddenLine => "lineas ocultas",
+        }
+    }
+
+    // Modos en los que se ve la superficie sombreada (y por tanto sus cascos translúcidos)
+    pub fn shows_surface(self) -> bool {
+        matches!(self, RenderMode::Shaded | RenderMode::ShadedWireframe)
+    }
+
+    // Modos que rasterizan las caras (con o sin color)
+    fn fills(self) -> bool {
+        matches!(
+            self,
+            RenderMode::Shaded | RenderMode::ShadedWireframe | RenderMode::HiddenLine
+        )
+    }
+}
+
+// Modo global + overrides por objeto (identificados por su índice en la lista de
+// objetos que recorre el HUD: dos cuerpos con el mismo shader son objetos distintos)
+pub struct RenderModes {
+    pub global: RenderMode,
+    overrides: Vec<(usize, RenderMode)>,
+}
+
+impl RenderModes {
+    pub fn new(global: RenderMode) -> Self {
+        Self {
+            global,
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn override_for(&self, target: usize) -> Option<RenderMode> {
+        self.overrides
+            .iter()
+            .find(|(t, _)| *t == target)
+            .map(|(_, m)| *m)
+    }
+
+    pub fn mode_for(&self, target: usize) -> RenderMode {
+        self.override_for(target).unwrap_or(self.global)
+    }
+
+    // None = modo global. Para un objeto: sigue al global -> cada modo -> sigue al global
+    pub fn cycle(&mut self, target: Option<usize>) {
+        let Some(target) = target else {
+            self.global = self.global.next();
+            return;
+        };
+        match self.overrides.iter().position(|(t, _)| *t == target) {
+            None => self.overrides.push((target, RenderMode::ALL[0])),
+            Some(i) => {
+                let mode = self.overrides[i].1;
+                if mode == RenderMode::ALL[RenderMode::ALL.len() - 1] {
+                    self.overrides.remove(i);
+                } else {
+                    self.overrides[i].1 = mode.next();
+                }
+            }
+        }
+    }
+}
+
+//
+// ----- MODOS DE SOMBREADO -----
+//
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ShadingMode {
+    // un color por cara con su normal geométrica
+    Flat,
+    // shader en cada vértice, color interpolado
+    Gouraud,
+    // normal y posición interpoladas, shader por píxel
+    Phong,
+}
+
+impl ShadingMode {
+    pub const ALL: [ShadingMode; 3] = [ShadingMode::Flat, ShadingMode::Gouraud, ShadingMode::Phong];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShadingMode::Flat => "plano",
+            ShadingMode::Gouraud => "gouraud",
+            ShadingMode::Phong => "phong",
+        }
+    }
+}
+
+// Malla + shader listos para rasterizar. En plano y Gouraud los colores se
+// evalúan al construirla; en Phong el shader se llama por píxel al rellenar.
+pub struct Surface<'a, F: Fn(&Fragment) -> Color> {
+    mesh: &'a Mesh,
+    normal_to_view: glm::Mat3,
+    shading: ShadingMode,
+    shade: F,
+    face_colors: Vec<Color>,
+    corner_colors: Vec<[Color; 3]>,
+}
+
+impl<'a, F: Fn(&Fragment) -> Color> Surface<'a, F> {
+    // `mv` solo se usa para llevar normales a vista (se asume escala uniforme)
+    pub fn new(mesh: &'a Mesh, mv: &glm::Mat4, shading: ShadingMode, shade: F) -> Self {
+        let mut surface = Self {
+            mesh,
+            normal_to_view: glm::mat4_to_mat3(mv),
+            shading,
+            shade,
+            face_colors: Vec::new(),
+            corner_colors: Vec::new(),
+        };
+        let faces = 0..mesh.faces.len();
+        match shading {
+            ShadingMode::Flat => {
+                surface.face_colors = faces
+                    .map(|fi| (surface.shade)(&surface.flat_fragment(fi)))
+                    .collect();
+            }
+            ShadingMode::Gouraud => {
+                surface.corner_colors = faces
+                    .map(|fi| [0, 1, 2].map(|k| (surface.shade)(&surface.corner_fragment(fi, k))))
+                    .collect();
+            }
+            ShadingMode::Phong => {}
+        }
+        surface
+    }
+
+    fn fragment(&self, p: Vec3, n: Vec3) -> Fragment {
+        Fragment {
+            p,
+            n,
+            n_view: glm::normalize(&(self.normal_to_view * n)),
+        }
+    }
+
+    fn flat_fragment(&self, fi: usize) -> Fragment {
+        let [i0, i1, i2] = self.mesh.faces[fi].vertex_indices;
+        let v = &self.mesh.vertices;
+        let centroid = (v[i0] + v[i1] + v[i2]) / 3.0;
+        self.fragment(centroid, self.mesh.face_normals[fi])
+    }
+
+    fn corner_fragment(&self, fi: usize, k: usize) -> Fragment {
+        let vi = self.mesh.faces[fi].vertex_indices[k];
+        self.fragment(self.mesh.vertices[vi], self.mesh.corner_normals[fi][k])
+    }
+
+    fn pixel_fragment(&self, fi: usize, w: [f32; 3]) -> Fragment {
+        let [i0, i1, i2] = self.mesh.faces[fi].vertex_indices;
+        let v = &self.mesh.vertices;
+        let [n0, n1, n2] = self.mesh.corner_normals[fi];
+        let p = v[i0] * w[0] + v[i1] * w[1] + v[i2] * w[2];
+        let n = n0 * w[0] + n1 * w[1] + n2 * w[2];
+        let len = n.magnitude();
+        let n = if len > 1e-6 { n / len } else { self.mesh.face_normals[fi] };
+        self.fragment(p, n)
+    }
+
+    // Color representativo de la cara (wireframe)
+    fn face_color(&self, fi: usize) -> Color {
+        match self.shading {
+            ShadingMode::Flat => self.face_colors[fi],
+            _ => (self.shade)(&self.flat_fragment(fi)),
+        }
+    }
+
+    fn pixel_color(&self, fi: usize, w: [f32; 3]) -> Color {
+        match self.shading {
+            ShadingMode::Flat => self.face_colors[fi],
+            ShadingMode::Gouraud => {
+                let [c0, c1, c2] = self.corner_colors[fi];
+                let mix = |a: u8, b: u8, c: u8| {
+                    (a as f32 * w[0] + b as f32 * w[1] + c as f32 * w[2]).clamp(0.0, 255.0) as u8
+                };
+                Color::new(
+                    mix(c0.r, c1.r, c2.r),
+                    mix(c0.g, c1.g, c2.g),
+                    mix(c0.b, c1.b, c2.b),
+                    mix(c0.a, c1.a, c2.a),
+                )
+            }
+            ShadingMode::Phong => (self.shade)(&self.pixel_fragment(fi, w)),
+        }
+    }
+}
+
+//
+// ----- PROYECCIÓN DE MALLAS -----
+//
+
+pub struct ProjectedMesh {
+    pub screen: Vec<Vec3>,
+    // cara orientada hacia la cámara (normal geométrica vs. rayo de vista)
+    pub is_front: Vec<bool>,
+}
+
+pub fn project_mesh(mesh: &Mesh, mv: &glm::Mat4, proj: &glm::Mat4, fb: &FrameBuffer) -> ProjectedMesh {
+    let mvp = proj * mv;
+    let screen = project_vertices_perspective(&mesh.vertices, &mvp, fb.width, fb.height);
+
+    let view: Vec<glm::Vec3> = mesh
+        .vertices
+        .iter()
+        .map(|v| {
+            let p = mv * glm::vec4(v.x, v.y, v.z, 1.0);
+            glm::vec3(p.x, p.y, p.z)
+        })
+        .collect();
+
+    // la cámara está en el origen de vista: de frente si la normal apunta hacia ella
+    let is_front = mesh
+        .faces
+        .iter()
+        .map(|f| {
+            let [i0, i1, i2] = f.vertex_indices;
+            let (va, vb, vc) = (view[i0], view[i1], view[i2]);
+            glm::dot(&glm::cross(&(vb - va), &(vc - va)), &va) < 0.0
+        })
+        .collect();
+
+    ProjectedMesh { screen, is_front }
+}
+
+// Vértice proyectado dentro del volumen de vista (evita líneas kilométricas
+// cuando un vértice queda detrás de la cámara)
+#[inline]
+fn in_depth_range(v: &Vec3) -> bool {
+    (-1.0..=1.0).contains(&v.z)
+}
+
+#[inline]
+fn biased(v: &Vec3) -> Vec3 {
+    glm::vec3(v.x, v.y, v.z - EDGE_DEPTH_BIAS)
+}
+
+//
+// ----- DIBUJO DE MALLAS SEGÚN EL MODO -----
+//
+
+pub fn draw_mesh<F: Fn(&Fragment) -> Color>(
+    fb: &mut FrameBuffer,
+    pm: &ProjectedMesh,
+    surface: &Surface<F>,
+    mode: RenderMode,
+    outline_thickness: i32,
+    prof: &mut Profiler,
+) {
+    let mesh = surface.mesh;
+    if mode.fills() {
+        let t_fill = prof.start();
+        // En hidden-line solo se llena el z-buffer: las caras tapan aristas pero no se ven
+        let color_write = mode != RenderMode::HiddenLine;
+        fb.set_color_write(color_write);
+        for (fi, f) in mesh.faces.iter().enumerate() {
+            let [i0, i1, i2] = f.vertex_indices;
+            let (a, b, c) = (&pm.screen[i0], &pm.screen[i1], &pm.screen[i2]);
+            if surface.shading == ShadingMode::Flat || !color_write {
+                if color_write {
+                    fb.set_color(surface.face_colors[fi]);
+                }
+                triangle_filled(fb, a, b, c);
+            } else {
+                triangle_filled_shaded(fb, a, b, c, |w| surface.pixel_color(fi, w));
+            }
+        }
+        fb.set_color_write(true);
+        prof.record("relleno", t_fill);
+    }
+
+    let t_lines = prof.start();
+    match mode {
+        RenderMode::Shaded => draw_silhouette(fb, mesh, pm, outline_thickness),
+        RenderMode::Wireframe => {
+            // Todas las aristas, sin prueba de profundidad
+            for (fi, f) in mesh.faces.iter().enumerate() {
+                let [i0, i1, i2] = f.vertex_indices;
+                let (a, b, c) = (&pm.screen[i0], &pm.screen[i1], &pm.screen[i2]);
+                if in_depth_range(a) && in_depth_range(b) && in_depth_range(c) {
+                    fb.set_color(surface.face_color(fi));
+                    triangle(fb, a, b, c);
+                }
+            }
+        }
+        RenderMode::ShadedWireframe => {
+            draw_silhouette(fb, mesh, pm, outline_thickness);
+            draw_edges_depth(fb, mesh, pm, true);
+        }
+        RenderMode::Vertices => {
+            fb.set_color(VERTEX_COLOR);
+            for v in pm.screen.iter().filter(|v| in_depth_range(v)) {
+                point(fb, v);
+            }
+        }
+        RenderMode::HiddenLine => draw_edges_depth(fb, mesh, pm, false),
+    }
+    prof.record("contornos", t_lines);
+}
+
+// Casco translúcido alrededor de un cuerpo (atmósfera, nubes): solo caras
+// frontales, mezcla alfa con lo ya dibujado y sin escribir profundidad.
+// Debe dibujarse después de toda la geometría opaca.
+pub fn draw_shell<F: Fn(&Fragment) -> Color>(
+    fb: &mut FrameBuffer,
+    pm: &ProjectedMesh,
+    surface: &Surface<F>,
+    prof: &mut Profiler,
+) {
+    let t_fill = prof.start();
+    fb.set_blend(true);
+    for (fi, f) in surface.mesh.faces.iter().enumerate() {
+        if !pm.is_front[fi] {
+            continue;
+        }
+        let [i0, i1, i2] = f.vertex_indices;
+        let (a, b, c) = (&pm.screen[i0], &pm.screen[i1], &pm.screen[i2]);
+        triangle_filled_shaded(fb, a, b, c, |w| surface.pixel_color(fi, w));
+    }
+    fb.set_blend(false);
+    prof.record("translucidos", t_fill);
+}
+
+// Contorno estilo cómic: aristas entre una cara frontal y una trasera
+fn draw_silhouette(fb: &mut FrameBuffer, mesh: &Mesh, pm: &ProjectedMesh, thickness: i32) {
+    if thickness < 0 {
+        return;
+    }
+    fb.set_color(Color::BLACK);
+    for (&(i0, i1), adj) in &mesh.edge_to_faces {
+        let draw = match adj.as_slice() {
+            [f0] => pm.is_front[*f0],
+            [f0, f1] => pm.is_front[*f0] ^ pm.is_front[*f1],
+            _ => false,
+        };
+        if draw {
+            let a = pm.screen[i0];
+            let b = pm.screen[i1];
+            line_depth_thick(fb, &a, &b, thickness);
+        }
+    }
+}
+
+// Aristas de 1 px con prueba de profundidad contra lo ya rasterizado
+fn draw_edges_depth(fb: &mut FrameBuffer, mesh: &Mesh, pm: &ProjectedMesh, front_only: bool) {
+    fb.set_color(WIRE_COLOR);
+    for (&(i0, i1), adj) in &mesh.edge_to_faces {
+        if front_only && !adj.iter().any(|&f| pm.is_front[f]) {
+            continue;
+        }
+        let (a, b) = (&pm.screen[i0], &pm.screen[i1]);
+        if in_depth_range(a) && in_depth_range(b) {
+            line_depth_thick(fb, &biased(a), &biased(b), 0);
+        }
+    }
+}
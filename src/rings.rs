@@ -0,0 +1,271 @@
+use anyhow::{anyhow, Result};
+use nalgebra_glm as glm;
+use raylib::prelude::{Color, Image};
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::Vec3;
+use crate::noise::{Fractal, Noise, fbm};
+use crate::shaders::smoothstep;
+use crate::shadow::ShadowMap;
+use crate::transform::project_vertices_perspective;
+use crate::triangle::triangle_filled_shaded;
+
+// Malla del anillo: segmentos alrededor del cuerpo y bandas radiales
+const RING_SEGMENTS: usize = 128;
+const RING_BANDS: usize = 24;
+// Luz mínima del anillo dentro de la sombra del cuerpo
+const RING_AMBIENT: f32 = 0.12;
+// Un anillo es una nube de partículas: aun con el Sol rasante dispersa algo de luz
+const RING_DIFFUSE_FLOOR: f32 = 0.5;
+// Luz que atraviesa el anillo cuando se mira por la cara no iluminada
+const RING_TRANSMISSION: f32 = 0.45;
+// El mapa de sombras es binario: solo proyectan sombra las bandas más densas
+const SHADOW_MIN_OPACITY: f32 = 0.35;
+
+// Perfil radial de color y densidad, de borde interior (0) a exterior (1)
+pub enum RingProfile {
+    // Bandas de ruido 1D con semilla; `bands` = número aproximado de bandas
+    Procedural { seed: u32, bands: f32 },
+    // Primera fila de una imagen: color (rgb) y densidad (alfa) por radio
+    Texture(Vec<[f32; 4]>),
+}
+
+// Sistema de anillos acoplable a cualquier cuerpo. Distancias en radios del cuerpo.
+pub struct RingSystem {
+    pub inner: f32,
+    pub outer: f32,
+    // inclinación (rad) respecto al ecuador del cuerpo
+    pub tilt: f32,
+    // tinte que multiplica el color del perfil
+    pub color: Vec3,
+    // opacidad donde la densidad del perfil es 1
+    pub opacity: f32,
+    pub profile: RingProfile,
+    // divisiones vacías: (radio central, ancho)
+    pub gaps: Vec<(f32, f32)>,
+}
+
+impl Default for RingSystem {
+    fn default() -> Self {
+        Self {
+            inner: 1.3,
+            outer: 2.2,
+            tilt: 0.0,
+            color: glm::vec3(0.85, 0.78, 0.65),
+            opacity: 0.8,
+            profile: RingProfile::Procedural {
+                seed: 1,
+                bands: 12.0,
+            },
+            gaps: Vec::new(),
+        }
+    }
+}
+
+impl RingProfile {
+    pub fn load_texture(path: &str) -> Result<Self> {
+        let image = Image::load_image(path).map_err(|e| anyhow!("{path}: {e}"))?;
+        let width = image.width().max(0) as usize;
+        let texels: Vec<[f32; 4]> = image
+            .get_image_data()
+            .iter()
+            .take(width)
+            .map(|c| [c.r, c.g, c.b, c.a].map(|v| v as f32 / 255.0))
+            .collect();
+        if texels.is_empty() {
+            return Err(anyhow!("{path}: imagen vacía"));
+        }
+        Ok(RingProfile::Texture(texels))
+    }
+
+    // Color y densidad en `t` (0 = borde interior, 1 = exterior)
+    fn sample(&self, t: f32) -> (Vec3, f32) {
+        match self {
+            RingProfile::Procedural { seed, bands } => {
+                let noise = Noise::new(*seed);
+                // fuera de la rejilla en y, z para que el Perlin no se anule
+                let p = glm::vec3(t * bands, 0.37, 0.71);
+                let density = fbm(p, &Fractal::octaves(5), |q| noise.perlin(q)) * 0.5 + 0.5;
+                let tone = noise.derive(1).perlin(glm::vec3(t * bands * 0.3, 0.5, 0.5));
+                let tint = glm::vec3(1.0, 1.0, 1.0) * (0.85 + 0.3 * tone);
+                (tint, smoothstep(0.3, 0.7, density))
+            }
+            RingProfile::Texture(texels) => {
+                let x = t * (texels.len() - 1) as f32;
+                let i = (x.floor() as usize).min(texels.len() - 1);
+                let j = (i + 1).min(texels.len() - 1);
+                let f = x - i as f32;
+                let [r, g, b, a] =
+                    [0, 1, 2, 3].map(|k| texels[i][k] * (1.0 - f) + texels[j][k] * f);
+                (glm::vec3(r, g, b), a)
+            }
+        }
+    }
+}
+
+impl RingSystem {
+    // Color y opacidad a distancia `r` (radios del cuerpo) del centro
+    pub fn sample(&self, r: f32) -> (Vec3, f32) {
+        let width = (self.outer - self.inner).max(1e-4);
+        let t = (r - self.inner) / width;
+        if !(0.0..=1.0).contains(&t) {
+            return (self.color, 0.0);
+        }
+        let (tint, density) = self.profile.sample(t);
+        // bordes suaves y divisiones con un 15% de transición
+        let edge = smoothstep(0.0, 0.03, t) * smoothstep(0.0, 0.03, 1.0 - t);
+        let gaps: f32 = self
+            .gaps
+            .iter()
+            .map(|&(c, w)| smoothstep(w * 0.5, w * 0.65, (r - c).abs()))
+            .product();
+        let alpha = (density * edge * gaps * self.opacity).clamp(0.0, 1.0);
+        (self.color.component_mul(&tint), alpha)
+    }
+
+    // Opacidad media de una banda [r0, r1] (para descartar bandas vacías)
+    fn band_opacity(&self, r0: f32, r1: f32) -> f32 {
+        let samples = 6;
+        let radius = |i: usize| r0 + (r1 - r0) * (i as f32 + 0.5) / samples as f32;
+        let sum: f32 = (0..samples).map(|i| self.sample(radius(i)).1).sum();
+        sum / samples as f32
+    }
+}
+
+//
+// ----- ANILLOS EN LA ESCENA -----
+//
+
+// Qué parte del anillo dibujar respecto al cuerpo: la de detrás va antes de sus
+// cascos translúcidos (atmósfera, nubes) y la de delante después
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum RingHalf {
+    Behind,
+    InFront,
+}
+
+// Anillos de un cuerpo en un frame: malla en el plano y = 0 local (en radios del
+// cuerpo) y su matriz de modelo
+pub struct RingInstance<'a> {
+    pub rings: &'a RingSystem,
+    pub model: glm::Mat4,
+    center: Vec3,
+    world: Vec<Vec3>,
+    triangles: Vec<[usize; 3]>,
+    shadow_triangles: Vec<[usize; 3]>,
+}
+
+impl<'a> RingInstance<'a> {
+    // `axis` = orientación del eje de giro del cuerpo (ver SpinAxis::orientation);
+    // `body_radius` en unidades de mundo
+    pub fn new(rings: &'a RingSystem, center: Vec3, axis: &glm::Mat4, body_radius: f32) -> Self {
+        let model = glm::translation(&center)
+            * axis
+            * glm::rotation(rings.tilt, &glm::vec3(1.0, 0.0, 0.0))
+            * glm::scaling(&glm::vec3(body_radius, body_radius, body_radius));
+
+        let radius = |band: usize| {
+            rings.inner + (rings.outer - rings.inner) * band as f32 / RING_BANDS as f32
+        };
+        // (RING_BANDS + 1) anillos de vértices con RING_SEGMENTS + 1 vértices cada uno
+        let world: Vec<Vec3> = (0..=RING_BANDS)
+            .flat_map(|band| {
+                let r = radius(band);
+                (0..=RING_SEGMENTS).map(move |i| {
+                    let a = i as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+                    glm::vec3(r * a.cos(), 0.0, r * a.sin())
+                })
+            })
+            .map(|v| {
+                let p = model * glm::vec4(v.x, v.y, v.z, 1.0);
+                glm::vec3(p.x, p.y, p.z)
+            })
+            .collect();
+
+        let mut triangles = Vec::new();
+        let mut shadow_triangles = Vec::new();
+        let row = RING_SEGMENTS + 1;
+        for band in 0..RING_BANDS {
+            let opacity = rings.band_opacity(radius(band), radius(band + 1));
+            if opacity < 0.01 {
+                continue;
+            }
+            let quads = (0..RING_SEGMENTS).flat_map(|i| {
+                let (i0, o0) = (band * row + i, (band + 1) * row + i);
+                let (i1, o1) = (i0 + 1, o0 + 1);
+                [[i0, o0, i1], [i1, o0, o1]]
+            });
+            if opacity >= SHADOW_MIN_OPACITY {
+                shadow_triangles.extend(quads.clone());
+            }
+            triangles.extend(quads);
+        }
+
+        Self {
+            rings,
+            model,
+            center,
+            world,
+            triangles,
+            shadow_triangles,
+        }
+    }
+
+    pub fn draw_shadow(&self, shadows: &mut ShadowMap) {
+        shadows.draw_triangles(&self.world, &self.shadow_triangles);
+    }
+
+    // Mezcla alfa sin escribir profundidad (igual que los cascos): debe dibujarse
+    // después de la geometría opaca. Ambas caras se iluminan con el Sol.
+    pub fn draw(
+        &self,
+        fb: &mut FrameBuffer,
+        view_proj: &glm::Mat4,
+        cam_pos: Vec3,
+        sun_pos: Vec3,
+        shadows: &ShadowMap,
+        half: RingHalf,
+    ) {
+        let screen = project_vertices_perspective(&self.world, view_proj, fb.width, fb.height);
+        let in_depth_range = |i: usize| (-1.0..=1.0).contains(&screen[i].z);
+
+        let normal = glm::normalize(&(glm::mat4_to_mat3(&self.model) * glm::vec3(0.0, 1.0, 0.0)));
+        let inv_model = glm::inverse(&self.model);
+        let center_dist2 = glm::distance2(&self.center, &cam_pos);
+
+        fb.set_blend(true);
+        for &[i0, i1, i2] in &self.triangles {
+            let (w0, w1, w2) = (self.world[i0], self.world[i1], self.world[i2]);
+            let behind = glm::distance2(&((w0 + w1 + w2) / 3.0), &cam_pos) > center_dist2;
+            if behind != (half == RingHalf::Behind) {
+                continue;
+            }
+            // sin recorte: se descarta el triángulo si un vértice sale del volumen de vista
+            if !(in_depth_range(i0) && in_depth_range(i1) && in_depth_range(i2)) {
+                continue;
+            }
+            triangle_filled_shaded(fb, &screen[i0], &screen[i1], &screen[i2], |bw| {
+                let p = w0 * bw[0] + w1 * bw[1] + w2 * bw[2];
+                let local = inv_model * glm::vec4(p.x, p.y, p.z, 1.0);
+                let (color, alpha) = self.rings.sample(local.x.hypot(local.z));
+
+                let to_sun = glm::normalize(&(sun_pos - p));
+                let ndl = glm::dot(&normal, &to_sun);
+                // cara iluminada: la que mira al Sol; vista desde la otra, luz transmitida
+                let lit_face = if ndl >= 0.0 { normal } else { -normal };
+                let facing = glm::dot(&lit_face, &(cam_pos - p)) >= 0.0;
+                let mut light = RING_DIFFUSE_FLOOR + (1.0 - RING_DIFFUSE_FLOOR) * ndl.abs();
+                if !facing {
+                    light *= RING_TRANSMISSION;
+                }
+                let k =
+                    RING_AMBIENT + (1.0 - RING_AMBIENT) * light * shadows.visibility(p, lit_face);
+
+                let channel = |c: f32| ((c * k).clamp(0.0, 1.0) * 255.0) as u8;
+                let a = (alpha * 255.0) as u8;
+                Color::new(channel(color.x), channel(color.y), channel(color.z), a)
+            });
+        }
+        fb.set_blend(false);
+    }
+}
@@ -0,0 +1,662 @@
+use anyhow::{anyhow, Result};
+use nalgebra_glm as glm;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::asteroids::{AsteroidBelt, MAX_ROCKS};
+use crate::params::{ConfigLine, config_lines, line_error, parse_numbers};
+use crate::registry::ShaderRegistry;
+use crate::rings::{RingProfile, RingSystem};
+use crate::shaders::{Biome, BodyShader, ProceduralParams};
+
+// Archivo con la descripción del sistema planetario
+pub const SCENE_PATH: &str = "assets/scene.cfg";
+
+// Orientación del eje de giro de un cuerpo respecto a la eclíptica (plano y = 0)
+#[derive(Copy, Clone)]
+pub struct SpinAxis {
+    // oblicuidad (rad): ángulo entre el eje y la normal de la eclíptica
+    pub tilt: f32,
+    // hacia dónde se inclina el polo norte (rad alrededor de +y, 0 = hacia +z)
+    pub pole_azimuth: f32,
+    // giro del polo alrededor de la normal de la eclíptica (rad/s)
+    pub precession: f32,
+}
+
+pub struct PlanetDef {
+    pub kind: BodyShader,
+    // radio de la órbita en unidades de `Scene::orbit_base`
+    pub orbit: f32,
+    pub orbit_speed: f32,
+    pub self_speed: f32,
+    pub scale: f32,
+    pub axis: SpinAxis,
+    pub rings: Option<RingSystem>,
+    pub moons: Vec<MoonDef>,
+}
+
+// Luna en órbita alrededor de su planeta (distancias relativas al radio del planeta)
+pub struct MoonDef {
+    pub kind: BodyShader,
+    pub scale: f32,
+    pub orbit_factor: f32,
+    pub orbit_speed: f32,
+    // fase inicial de la órbita y amplitud vertical (relativa a la esfera base)
+    pub phase: f32,
+    pub bob: f32,
+    pub self_speed: f32,
+    pub axis: SpinAxis,
+    pub rings: Option<RingSystem>,
+}
+
+// Sistema planetario: el Sol en el origen y los planetas con sus lunas
+pub struct Scene {
+    // escala del Sol respecto a la esfera base
+    pub sun_scale: f32,
+    // radio de la primera órbita, en radios de la esfera base
+    pub orbit_base: f32,
+    pub planets: Vec<PlanetDef>,
+    pub belt: Option<AsteroidBelt>,
+}
+
+impl SpinAxis {
+    // Eje vertical, sin precesión
+    pub const UPRIGHT: Self = Self {
+        tilt: 0.0,
+        pole_azimuth: 0.0,
+        precession: 0.0,
+    };
+
+    pub fn tilted(tilt: f32) -> Self {
+        Self {
+            tilt,
+            ..Self::UPRIGHT
+        }
+    }
+
+    // Rotación que lleva el eje +y del modelo al eje de giro en el instante `time`.
+    // Se aplica antes del giro propio: la superficie, los cascos, las órbitas de las
+    // lunas y los anillos quedan alineados con el ecuador inclinado.
+    pub fn orientation(&self, time: f32) -> glm::Mat4 {
+        let up = glm::vec3(0.0, 1.0, 0.0);
+        let azimuth = self.pole_azimuth + self.precession * time;
+        glm::rotation(azimuth, &up)
+            * glm::rotation(self.tilt, &glm::vec3(1.0, 0.0, 0.0))
+            * glm::rotation(-azimuth, &up)
+    }
+}
+
+impl PlanetDef {
+    fn new(kind: BodyShader) -> Self {
+        Self {
+            kind,
+            orbit: 1.0,
+            orbit_speed: 0.3,
+            self_speed: 1.0,
+            scale: 1.0,
+            axis: SpinAxis::UPRIGHT,
+            rings: None,
+            moons: Vec::new(),
+        }
+    }
+}
+
+impl MoonDef {
+    fn new(kind: BodyShader) -> Self {
+        Self {
+            kind,
+            scale: 0.3,
+            orbit_factor: 3.0,
+            orbit_speed: 1.5,
+            phase: 0.0,
+            bob: 0.0,
+            self_speed: 1.0,
+            axis: SpinAxis::UPRIGHT,
+            rings: None,
+        }
+    }
+}
+
+//
+// ----- SISTEMA POR DEFECTO -----
+//
+
+impl Scene {
+    // El sistema original; `seed` es la del planeta procedural exterior
+    pub fn builtin(seed: u32) -> Self {
+        let planet = |kind, orbit, orbit_speed, self_speed, scale, tilt, moons| PlanetDef {
+            kind,
+            orbit,
+            orbit_speed,
+            self_speed,
+            scale,
+            axis: SpinAxis::tilted(tilt),
+            rings: None,
+            moons,
+        };
+        Self {
+            sun_scale: 3.0,
+            orbit_base: 6.0,
+            planets: vec![
+                planet(BodyShader::Mercury, 1.0, 0.6, 2.0, 0.45, 0.0, vec![]),
+                planet(BodyShader::Venus, 1.6, 0.45, 1.6, 0.8, 0.0, vec![]),
+                planet(
+                    BodyShader::Rocky,
+                    2.3,
+                    0.35,
+                    1.8,
+                    0.9,
+                    0.41,
+                    vec![MoonDef {
+                        kind: BodyShader::Moon,
+                        scale: 0.35,
+                        orbit_factor: 3.0,
+                        orbit_speed: 1.6,
+                        phase: 0.0,
+                        bob: 0.3,
+                        self_speed: 1.2,
+                        axis: SpinAxis::UPRIGHT,
+                        rings: None,
+                    }],
+                ),
+                planet(BodyShader::Mars, 3.1, 0.25, 1.5, 0.75, 0.44, vec![]),
+                PlanetDef {
+                    rings: Some(jupiter_rings()),
+                    ..planet(
+                        BodyShader::GasGiant,
+                        4.3,
+                        0.18,
+                        1.2,
+                        1.7,
+                        0.45,
+                        vec![MoonDef {
+                            kind: BodyShader::Volcanic,
+                            scale: 0.30,
+                            orbit_factor: 2.4,
+                            orbit_speed: 2.2,
+                            phase: 1.3,
+                            bob: 0.1,
+                            self_speed: 0.9,
+                            axis: SpinAxis::UPRIGHT,
+                            rings: None,
+                        }],
+                    )
+                },
+                planet(
+                    BodyShader::Procedural(ProceduralParams::from_seed(seed)),
+                    5.4,
+                    0.12,
+                    1.0,
+                    1.1,
+                    0.2,
+                    vec![],
+                ),
+            ],
+            // entre Marte y Júpiter
+            belt: Some(AsteroidBelt::default()),
+        }
+    }
+
+    // Órbita más externa (en unidades de orbit_base)
+    pub fn max_orbit(&self) -> f32 {
+        self.planets.iter().map(|p| p.orbit).fold(1.0, f32::max)
+    }
+}
+
+// Anillos azulados de Júpiter con una división (los mismos de assets/scene.cfg)
+fn jupiter_rings() -> RingSystem {
+    RingSystem {
+        inner: 1.2,
+        outer: 1.75,
+        tilt: 0.0,
+        color: glm::vec3(0.78, 0.82, 0.95),
+        opacity: 0.85,
+        profile: RingProfile::Procedural {
+            seed: 5,
+            bands: 10.0,
+        },
+        gaps: vec![(1.5, 0.05)],
+    }
+}
+
+//
+// ----- ARCHIVO DE ESCENA -----
+//
+// Mismo formato que los parámetros de shaders, con secciones repetibles:
+//
+//   [system]            sun_scale, orbit_base
+//   [planet]            kind, orbit, orbit_speed, self_speed, scale, tilt,
+//                       pole_azimuth, precession
+//   [moon]              luna del último [planet]: kind, scale, orbit_factor,
+//                       orbit_speed, phase, bob, self_speed, tilt, pole_azimuth,
+//                       precession
+//   [rings]             anillos del último [planet] o [moon]: inner, outer, tilt,
+//                       color, opacity, profile, gap (repetible: radio, ancho)
+//   [belt]              cinturón de asteroides (uno): inner, outer, count, seed,
+//                       thickness, size (mín, máx), orbit_speed, color
+//
+// `kind` es el nombre de un shader del registro (mercury, venus, earth, mars,
+// jupiter, moon, io y los que se registren) o `procedural [semilla] [rocky|icy|lava|gas]`.
+// `profile` de los anillos es `procedural [semilla] [bandas]` o `texture imagen.png`.
+
+fn biome_from_name(name: &str) -> Option<Biome> {
+    match name {
+        "rocky" => Some(Biome::Rocky),
+        "icy" => Some(Biome::Icy),
+        "lava" => Some(Biome::Lava),
+        "gas" => Some(Biome::Gas),
+        _ => None,
+    }
+}
+
+// Lo que necesita el parser para resolver `kind`; `default_seed` se usa para
+// `procedural` sin semilla explícita
+struct KindContext<'a> {
+    registry: &'a ShaderRegistry,
+    default_seed: u32,
+}
+
+fn kind_from_value(value: &str, ctx: &KindContext) -> Result<BodyShader> {
+    let mut words = value.split_whitespace();
+    let kind = match words.next().unwrap_or("") {
+        "procedural" => {
+            let seed = match words.next() {
+                Some(s) => s.parse().map_err(|_| anyhow!("semilla inválida '{s}'"))?,
+                None => ctx.default_seed,
+            };
+            let params = match words.next() {
+                Some(b) => {
+                    let biome =
+                        biome_from_name(b).ok_or_else(|| anyhow!("bioma desconocido '{b}'"))?;
+                    ProceduralParams::with_biome(seed, biome)
+                }
+                None => ProceduralParams::from_seed(seed),
+            };
+            BodyShader::Procedural(params)
+        }
+        name => ctx.registry.get(name).ok_or_else(|| {
+            let known: Vec<&str> = ctx.registry.names().collect();
+            anyhow!("shader desconocido '{name}' (disponibles: {}, procedural)", known.join(", "))
+        })?,
+    };
+    if let Some(extra) = words.next() {
+        return Err(anyhow!("sobra '{extra}' en kind"));
+    }
+    Ok(kind)
+}
+
+fn profile_from_value(value: &str) -> Result<RingProfile> {
+    let mut words = value.split_whitespace();
+    let profile = match words.next().unwrap_or("") {
+        "procedural" => {
+            let seed = match words.next() {
+                Some(s) => s.parse().map_err(|_| anyhow!("semilla inválida '{s}'"))?,
+                None => 1,
+            };
+            let bands = match words.next() {
+                Some(b) => b.parse().map_err(|_| anyhow!("bandas inválidas '{b}'"))?,
+                None => 12.0,
+            };
+            RingProfile::Procedural { seed, bands }
+        }
+        "texture" => {
+            let path = words.next().ok_or_else(|| anyhow!("falta la imagen"))?;
+            RingProfile::load_texture(path)?
+        }
+        other => return Err(anyhow!("perfil desconocido '{other}' (procedural, texture)")),
+    };
+    if let Some(extra) = words.next() {
+        return Err(anyhow!("sobra '{extra}' en profile"));
+    }
+    Ok(profile)
+}
+
+fn number(value: &str) -> Result<f32> {
+    match parse_numbers(value).as_deref() {
+        Some([v]) => Ok(*v),
+        _ => Err(anyhow!("se esperaba un número: {value}")),
+    }
+}
+
+enum Target {
+    None,
+    System,
+    Planet,
+    Moon,
+    PlanetRings,
+    MoonRings,
+    Belt,
+}
+
+impl Scene {
+    // `registry` resuelve los nombres de `kind`
+    pub fn parse(
+        text: &str,
+        origin: &str,
+        registry: &ShaderRegistry,
+        default_seed: u32,
+    ) -> Result<Self> {
+        let ctx = KindContext {
+            registry,
+            default_seed,
+        };
+        let mut scene = Scene {
+            planets: Vec::new(),
+            belt: None,
+            ..Scene::builtin(default_seed)
+        };
+        let mut target = Target::None;
+        // línea de la sección abierta: ahí se informan los errores entre claves
+        let mut section_line = 0;
+
+        for (lineno, line) in config_lines(text, origin)? {
+            let err = |e: anyhow::Error| line_error(origin, lineno, e);
+            if let ConfigLine::Section(_) = line {
+                scene
+                    .check_section(&target)
+                    .map_err(|e| line_error(origin, section_line, e))?;
+                section_line = lineno;
+            }
+            match line {
+                ConfigLine::Section("system") => target = Target::System,
+                ConfigLine::Section("planet") => {
+                    scene.planets.push(PlanetDef::new(BodyShader::Rocky));
+                    target = Target::Planet;
+                }
+                ConfigLine::Section("moon") => {
+                    let planet = scene
+                        .planets
+                        .last_mut()
+                        .ok_or_else(|| err(anyhow!("[moon] antes de cualquier [planet]")))?;
+                    planet.moons.push(MoonDef::new(BodyShader::Moon));
+                    target = Target::Moon;
+                }
+                ConfigLine::Section("rings") => {
+                    let planet = scene
+                        .planets
+                        .last_mut()
+                        .ok_or_else(|| err(anyhow!("[rings] antes de cualquier [planet]")))?;
+                    // tras una [moon] los anillos son de esa luna
+                    target = match (&target, planet.moons.last_mut()) {
+                        (Target::Moon, Some(moon)) => {
+                            moon.rings = Some(RingSystem::default());
+                            Target::MoonRings
+                        }
+                        _ => {
+                            planet.rings = Some(RingSystem::default());
+                            Target::PlanetRings
+                        }
+                    };
+                }
+                ConfigLine::Section("belt") => {
+                    if scene.belt.is_some() {
+                        return Err(err(anyhow!("solo puede haber un [belt]")));
+                    }
+                    scene.belt = Some(AsteroidBelt::default());
+                    target = Target::Belt;
+                }
+                ConfigLine::Section(other) => {
+                    return Err(err(anyhow!("sección desconocida [{other}]")));
+                }
+                ConfigLine::Entry(key, value) => match target {
+                    Target::None => return Err(err(anyhow!("clave fuera de una [sección]"))),
+                    Target::System => scene.set_system(key, value).map_err(err)?,
+                    Target::Planet => {
+                        let planet = scene.planets.last_mut().expect("[planet] recién creado");
+                        set_planet(planet, key, value, &ctx).map_err(err)?;
+                    }
+                    Target::Moon => {
+                        let moon = scene
+                            .planets
+                            .last_mut()
+                            .and_then(|p| p.moons.last_mut())
+                            .expect("[moon] recién creada");
+                        set_moon(moon, key, value, &ctx).map_err(err)?;
+                    }
+                    Target::PlanetRings | Target::MoonRings => {
+                        let planet = scene.planets.last_mut().expect("[planet] de los anillos");
+                        let rings = match target {
+                            Target::MoonRings => {
+                                planet.moons.last_mut().and_then(|m| m.rings.as_mut())
+                            }
+                            _ => planet.rings.as_mut(),
+                        };
+                        let rings = rings.expect("[rings] recién creados");
+                        set_rings(rings, key, value).map_err(err)?;
+                    }
+                    Target::Belt => {
+                        let belt = scene.belt.as_mut().expect("[belt] recién creado");
+                        set_belt(belt, key, value).map_err(err)?;
+                    }
+                },
+            }
+        }
+        scene
+            .check_section(&target)
+            .map_err(|e| line_error(origin, section_line, e))?;
+        Ok(scene)
+    }
+
+    // Comprobaciones entre claves de una misma sección, al cerrarla (cada clave
+    // por separado puede quedar a medias respecto a los valores por defecto)
+    fn check_section(&self, target: &Target) -> Result<()> {
+        let planet = self.planets.last();
+        let rings = match target {
+            Target::PlanetRings => planet.and_then(|p| p.rings.as_ref()),
+            Target::MoonRings => planet
+                .and_then(|p| p.moons.last())
+                .and_then(|m| m.rings.as_ref()),
+            _ => None,
+        };
+        if let Some(r) = rings
+            && !(0.0 < r.inner && r.inner < r.outer)
+        {
+            return Err(anyhow!(
+                "[rings] con inner = {} y outer = {}: se necesita 0 < inner < outer",
+                r.inner,
+                r.outer
+            ));
+        }
+        if let (Target::Belt, Some(b)) = (target, &self.belt)
+            && b.outer <= b.inner
+        {
+            return Err(anyhow!(
+                "[belt] con inner = {} y outer = {}: se necesita inner < outer",
+                b.inner,
+                b.outer
+            ));
+        }
+        Ok(())
+    }
+
+    // Escena del archivo o, si no existe, el sistema por defecto
+    pub fn load(path: &str, registry: &ShaderRegistry, default_seed: u32) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text, path, registry, default_seed),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::builtin(default_seed)),
+            Err(e) => Err(anyhow!("{path}: {e}")),
+        }
+    }
+
+    fn set_system(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "sun_scale" => self.sun_scale = number(value)?,
+            "orbit_base" => self.orbit_base = number(value)?,
+            _ => return Err(anyhow!("clave desconocida '{key}'")),
+        }
+        Ok(())
+    }
+}
+
+fn set_planet(p: &mut PlanetDef, key: &str, value: &str, ctx: &KindContext) -> Result<()> {
+    match key {
+        "kind" => p.kind = kind_from_value(value, ctx)?,
+        "orbit" => p.orbit = number(value)?,
+        "orbit_speed" => p.orbit_speed = number(value)?,
+        "self_speed" => p.self_speed = number(value)?,
+        "scale" => p.scale = number(value)?,
+        _ => return set_axis(&mut p.axis, key, value),
+    }
+    Ok(())
+}
+
+fn set_moon(m: &mut MoonDef, key: &str, value: &str, ctx: &KindContext) -> Result<()> {
+    match key {
+        "kind" => m.kind = kind_from_value(value, ctx)?,
+        "scale" => m.scale = number(value)?,
+        "orbit_factor" => m.orbit_factor = number(value)?,
+        "orbit_speed" => m.orbit_speed = number(value)?,
+        "phase" => m.phase = number(value)?,
+        "bob" => m.bob = number(value)?,
+        "self_speed" => m.self_speed = number(value)?,
+        _ => return set_axis(&mut m.axis, key, value),
+    }
+    Ok(())
+}
+
+// Claves del eje de giro, comunes a planetas y lunas
+fn set_axis(axis: &mut SpinAxis, key: &str, value: &str) -> Result<()> {
+    match key {
+        "tilt" => axis.tilt = number(value)?,
+        "pole_azimuth" => axis.pole_azimuth = number(value)?,
+        "precession" => axis.precession = number(value)?,
+        _ => return Err(anyhow!("clave desconocida '{key}'")),
+    }
+    Ok(())
+}
+
+fn set_rings(r: &mut RingSystem, key: &str, value: &str) -> Result<()> {
+    match key {
+        "inner" => r.inner = number(value)?,
+        "outer" => r.outer = number(value)?,
+        "tilt" => r.tilt = number(value)?,
+        "opacity" => r.opacity = number(value)?,
+        "color" => match parse_numbers(value).as_deref() {
+            Some(&[red, green, blue]) => r.color = glm::vec3(red, green, blue),
+            _ => return Err(anyhow!("se esperaban tres números (r, g, b): {value}")),
+        },
+        "profile" => r.profile = profile_from_value(value)?,
+        "gap" => match parse_numbers(value).as_deref() {
+            Some(&[center, width]) => r.gaps.push((center, width)),
+            _ => return Err(anyhow!("se esperaban dos números (radio, ancho): {value}")),
+        },
+        _ => return Err(anyhow!("clave desconocida '{key}'")),
+    }
+    Ok(())
+}
+
+fn positive(v: f32) -> Result<f32> {
+    if v > 0.0 {
+        Ok(v)
+    } else {
+        Err(anyhow!("se esperaba un número mayor que 0: {v}"))
+    }
+}
+
+fn set_belt(b: &mut AsteroidBelt, key: &str, value: &str) -> Result<()> {
+    match key {
+        "inner" => b.inner = positive(number(value)?)?,
+        "outer" => b.outer = positive(number(value)?)?,
+        "count" => match value.parse() {
+            Ok(count) if (1..=MAX_ROCKS).contains(&count) => b.count = count,
+            _ => return Err(anyhow!("count va de 1 a {MAX_ROCKS}: {value}")),
+        },
+        "seed" => match value.parse() {
+            Ok(seed) => b.seed = seed,
+            Err(_) => return Err(anyhow!("semilla inválida '{value}'")),
+        },
+        "thickness" => match number(value)? {
+            t if (0.0..=FRAC_PI_2).contains(&t) => b.thickness = t,
+            _ => return Err(anyhow!("thickness va de 0 a π/2: {value}")),
+        },
+        "orbit_speed" => b.orbit_speed = number(value)?,
+        "size" => match parse_numbers(value).as_deref() {
+            Some(&[min, max]) if 0.0 < min && min <= max => (b.size_min, b.size_max) = (min, max),
+            _ => return Err(anyhow!("se esperaban mín y máx (0 < mín <= máx): {value}")),
+        },
+        "color" => match parse_numbers(value).as_deref() {
+            Some(&[red, green, blue]) => b.color = glm::vec3(red, green, blue),
+            _ => return Err(anyhow!("se esperaban tres números (r, g, b): {value}")),
+        },
+        _ => return Err(anyhow!("clave desconocida '{key}'")),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Scene> {
+        Scene::parse(text, "test.cfg", &ShaderRegistry::with_builtins(), 7)
+    }
+
+    fn error(text: &str) -> String {
+        match parse(text) {
+            Ok(_) => panic!("se aceptó:\n{text}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn rings_need_inner_below_outer() {
+        let text = "[planet]\norbit = 2.0\n\n[rings]\ninner = 2.5\nouter = 2.5\n";
+        let err = error(text);
+        assert!(err.starts_with("test.cfg:4: [rings]"), "{err}");
+
+        // también al cerrar la sección con otra, y sobre los valores por defecto
+        let text = "[planet]\n[rings]\ninner = 3.0\n[planet]\n";
+        let err = error(text);
+        assert!(err.starts_with("test.cfg:2: [rings]"), "{err}");
+
+        let text = "[planet]\n[moon]\n[rings]\ninner = 0\nouter = 1.5\n";
+        let err = error(text);
+        assert!(err.starts_with("test.cfg:3: [rings]"), "{err}");
+    }
+
+    #[test]
+    fn rings_above_the_defaults() {
+        let text = "[planet]\n[rings]\ninner = 3.0\nouter = 4.0\n";
+        let scene = parse(text).unwrap_or_else(|e| panic!("{e}"));
+        let rings = scene.planets[0].rings.as_ref().unwrap();
+        assert_eq!((rings.inner, rings.outer), (3.0, 4.0));
+    }
+
+    #[test]
+    fn belt_radii() {
+        let text = "[belt]\ninner = 4.0\nouter = 4.0\n";
+        let err = error(text);
+        assert!(err.starts_with("test.cfg:1: [belt]"), "{err}");
+
+        // inner por encima del outer por defecto
+        let err = error("[planet]\n[belt]\ninner = 5.0\n");
+        assert!(err.starts_with("test.cfg:2: [belt]"), "{err}");
+
+        let err = error("[belt]\ninner = 0\n");
+        assert!(err.starts_with("test.cfg:2: "), "{err}");
+        let err = error("[belt]\nouter = -1\n");
+        assert!(err.starts_with("test.cfg:2: "), "{err}");
+    }
+
+    #[test]
+    fn belt_values() {
+        for (line, key) in [
+            (format!("count = {}", MAX_ROCKS + 1), "count"),
+            ("count = 0".to_string(), "count"),
+            ("thickness = -0.1".to_string(), "thickness"),
+            ("thickness = 2.0".to_string(), "thickness"),
+            ("size = 0.05, 0.01".to_string(), "mín"),
+            ("size = 0, 0.01".to_string(), "mín"),
+            ("size = 0.05".to_string(), "mín"),
+        ] {
+            let err = error(&format!("[belt]\n\n{line}\n"));
+            assert!(err.starts_with("test.cfg:3: "), "{line}: {err}");
+            assert!(err.contains(key), "{line}: {err}");
+        }
+
+        let text = format!("[belt]\ninner = 2\nouter = 3\ncount = {MAX_ROCKS}\nsize = 0.1, 0.1\n");
+        let scene = parse(&text).unwrap_or_else(|e| panic!("{e}"));
+        let belt = scene.belt.unwrap();
+        assert_eq!((belt.inner, belt.outer, belt.count), (2.0, 3.0, MAX_ROCKS));
+        assert_eq!((belt.size_min, belt.size_max), (0.1, 0.1));
+    }
+}
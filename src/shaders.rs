@@ -1,9 +1,24 @@
 use nalgebra_glm as glm;
 use raylib::prelude::Color;
+use std::sync::Arc;
 
 use crate::geom::Vec3;
+use crate::glare::{CoronaParams, FlareParams};
+use crate::noise::{Fractal, Noise, domain_warp, fbm, ridged, turbulence};
+use crate::params::{self, ParamSection, ParamSections, params_struct};
+use crate::registry::SurfaceShader;
+use crate::sky::SkyParams;
+
+// Punto de superficie a sombrear: posición y normal en espacio modelo + normal en vista.
+// Según el modo de sombreado viene de la cara, de un vértice o interpolado por píxel.
+#[derive(Copy, Clone, Debug)]
+pub struct Fragment {
+    pub p: Vec3,
+    pub n: Vec3,
+    pub n_view: Vec3,
+}
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub enum BodyShader {
     Star,
     Rocky,
@@ -12,6 +27,29 @@ pub enum BodyShader {
     Mercury,
     Venus,
     Mars,
+    Volcanic,
+    // Planeta generado a partir de una semilla (ver ProceduralParams)
+    Procedural(ProceduralParams),
+    // Shader registrado desde fuera (ver ShaderRegistry::register)
+    Custom(Arc<dyn SurfaceShader>),
+}
+
+impl BodyShader {
+    // Nombre para HUD y selección de objetos
+    pub fn label(&self) -> &'static str {
+        match self {
+            BodyShader::Star => "Sol",
+            BodyShader::Rocky => "Tierra",
+            BodyShader::GasGiant => "Jupiter",
+            BodyShader::Moon => "Luna",
+            BodyShader::Mercury => "Mercurio",
+            BodyShader::Venus => "Venus",
+            BodyShader::Mars => "Marte",
+            BodyShader::Volcanic => "Io",
+            BodyShader::Procedural(p) => p.biome.label(),
+            BodyShader::Custom(s) => s.label(),
+        }
+    }
 }
 
 fn spherical_coords(local: Vec3) -> (f32, f32, f32) {
@@ -33,64 +71,207 @@ fn lerp(a: glm::Vec3, b: glm::Vec3, t: f32) -> glm::Vec3 {
     a * (1.0 - t) + b * t
 }
 
-fn cheap_noise(lat: f32, lon: f32, time: f32, scale: f32) -> f32 {
-    let s = (lat * scale + time * 0.5).sin();
-    let c = (lon * scale * 1.3 - time * 0.7).cos();
-    let s2 = ((lat + lon) * scale * 0.7 + time * 0.31).sin();
-    (0.5 * s + 0.35 * c + 0.15 * s2) * 0.8
+pub(crate) fn smoothstep(e0: f32, e1: f32, x: f32) -> f32 {
+    let t = saturate((x - e0) / (e1 - e0));
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Ruido de las superficies del sistema. Se evalúa sobre la dirección unitaria
+// (espacio modelo) del punto, así no hay costura en la longitud ni pellizco en los
+// polos; cada cuerpo usa su propio canal con derive().
+const SURFACE_NOISE: Noise = Noise::new(1977);
+
+//
+// ----- LUZ DEL SOL -----
+//
+
+// Brillo mínimo del lado nocturno y semiancho del terminador (en dot(n, sol))
+const NIGHT_AMBIENT: f32 = 0.04;
+const TERMINATOR_SOFTNESS: f32 = 0.15;
+
+// Dirección hacia el Sol en espacio modelo del cuerpo: incluye la rotación
+// propia, así los accidentes del terreno cruzan el terminador al girar
+pub fn sun_direction_model(model: &glm::Mat4, body_center: Vec3, sun_pos: Vec3) -> Vec3 {
+    let to_sun = sun_pos - body_center;
+    if to_sun.magnitude() < 1e-5 {
+        return glm::vec3(0.0, 1.0, 0.0);
+    }
+    let local = glm::inverse(model) * glm::vec4(to_sun.x, to_sun.y, to_sun.z, 0.0);
+    glm::normalize(&glm::vec3(local.x, local.y, local.z))
+}
+
+// Luz del Sol que recibe un fragmento
+#[derive(Copy, Clone, Debug)]
+pub struct SunLight {
+    // dirección hacia el Sol en espacio modelo (ver sun_direction_model)
+    pub dir: Vec3,
+    // fracción no tapada por otros cuerpos (mapa de sombras), 1 = sin sombra
+    pub visibility: f32,
+}
+
+// Hemisferio iluminado con terminador suave y lado nocturno casi negro
+fn sun_light(n: Vec3, sun: &SunLight) -> f32 {
+    let ndl = glm::dot(&n, &sun.dir);
+    let day = smoothstep(-TERMINATOR_SOFTNESS, TERMINATOR_SOFTNESS, ndl);
+    let diffuse = 0.35 + 0.65 * ndl.max(0.0);
+    NIGHT_AMBIENT + (1.0 - NIGHT_AMBIENT) * day * diffuse * sun.visibility
+}
+
+//
+// ----- RELIEVE (BUMP MAPPING) -----
+//
+// Los accidentes del terreno se describen con un campo de alturas sobre la dirección
+// unitaria del punto. Su gradiente inclina la normal de sombreado: las laderas que
+// miran al Sol se aclaran y las opuestas se oscurecen, sobre todo cerca del
+// terminador, donde la luz llega rasante.
+
+// Paso de las diferencias finitas sobre la esfera unidad
+const BUMP_EPSILON: f32 = 2e-3;
+
+// Normal `n` inclinada por el gradiente tangencial de `height` en `d` (unitaria).
+// `strength` escala las alturas (0 = sin relieve).
+fn bump_normal(n: Vec3, d: Vec3, strength: f32, height: impl Fn(Vec3) -> f32) -> Vec3 {
+    if strength <= 0.0 {
+        return n;
+    }
+    // Base tangente en d; el eje auxiliar evita la degeneración en los polos
+    let helper = if d.y.abs() < 0.99 {
+        glm::vec3(0.0, 1.0, 0.0)
+    } else {
+        glm::vec3(1.0, 0.0, 0.0)
+    };
+    let tangent = glm::normalize(&glm::cross(&helper, &d));
+    let bitangent = glm::cross(&d, &tangent);
+
+    let h0 = height(d);
+    let dh_t = (height(glm::normalize(&(d + tangent * BUMP_EPSILON))) - h0) / BUMP_EPSILON;
+    let dh_b = (height(glm::normalize(&(d + bitangent * BUMP_EPSILON))) - h0) / BUMP_EPSILON;
+    glm::normalize(&(n - (tangent * dh_t + bitangent * dh_b) * strength))
+}
+
+// Cuenco de cráter gaussiano centrado en (c_lat, c_lon); d_lon se envuelve a [-π, π]
+fn crater_distance2(lat: f32, lon: f32, c_lat: f32, c_lon: f32) -> f32 {
+    let d_lat = lat - c_lat;
+    let d_lon = (lon - c_lon + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI)
+        - std::f32::consts::PI;
+    d_lat * d_lat + d_lon * d_lon
+}
+
+//
+// ----- CANAL EMISIVO -----
+//
+// Luz propia de la superficie (ciudades, lava, relámpagos). Se suma después de
+// la iluminación, así que brilla aunque al punto no le llegue el Sol.
+
+// 0 de día, 1 de noche, con transición a lo largo del terminador; las sombras
+// (eclipses, nubes) cuentan como noche
+fn night_amount(n: Vec3, sun: &SunLight) -> f32 {
+    let ndl = glm::dot(&n, &sun.dir);
+    1.0 - smoothstep(-0.20, 0.15, ndl) * sun.visibility
+}
+
+fn hash11(x: f32) -> f32 {
+    ((x.sin() * 43758.547).fract()).abs()
+}
+
+// Dirección unitaria a partir de (lat, lon), inversa de spherical_coords
+fn dir_from_lat_lon(lat: f32, lon: f32) -> Vec3 {
+    glm::vec3(lat.cos() * lon.cos(), lat.sin(), lat.cos() * lon.sin())
+}
+
+// Luces de ciudades: puntos agrupados en regiones, solo donde `land` > 0
+fn city_lights(d: Vec3, land: f32) -> f32 {
+    let noise = SURFACE_NOISE.derive(3);
+    let regions = saturate((fbm(d * 2.5, &Fractal::octaves(3), |p| noise.simplex(p)) + 0.05) * 4.0);
+    // cada celda de Worley es una ciudad: núcleo brillante y periferia tenue
+    let (f1, _) = noise.worley(d * 45.0);
+    let dots = saturate(1.0 - f1 * 2.2).powi(3);
+    let sprawl = saturate(noise.value(d * 30.0) * 0.5 + 0.3) * 0.35;
+    land * regions * (dots + sprawl)
+}
+
+// Relámpagos: destellos breves en celdas que cambian de sitio en cada intervalo
+fn lightning(d: Vec3, time: f32) -> f32 {
+    let mut total = 0.0;
+    for i in 0..6 {
+        let fi = i as f32;
+        let t = time * 2.5 + fi * 0.37;
+        let slot = t.floor();
+        if hash11(slot * 12.9898 + fi * 78.233) < 0.75 {
+            continue;
+        }
+        let flash = (-t.fract() * 10.0).exp();
+        let lat = (hash11(slot * 3.1 + fi * 1.7) - 0.5) * 1.6;
+        let lon = hash11(slot * 7.7 + fi * 5.3) * std::f32::consts::TAU - std::f32::consts::PI;
+        let d2 = glm::distance2(&d, &dir_from_lat_lon(lat, lon));
+        total += flash * (-d2 * 350.0).exp();
+    }
+    saturate(total)
 }
 
 //
 // ----- SOL -----
 //
 
-fn shade_star(p: Vec3, n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Color {
-    let local = p - center;
-    let (lat, lon, r_len) = spherical_coords(local);
-    let r_norm = saturate(r_len / radius);
+params_struct! {
+    pub struct StarParams {
+        core: glm::Vec3 = glm::vec3(1.05, 1.00, 0.94),
+        mid: glm::Vec3 = glm::vec3(1.00, 0.82, 0.35),
+        corona: glm::Vec3 = glm::vec3(1.00, 0.48, 0.08),
+        halo: glm::Vec3 = glm::vec3(1.00, 0.90, 0.60),
+        bright_cell: glm::Vec3 = glm::vec3(1.3, 1.15, 0.7),
+        dark_cell: glm::Vec3 = glm::vec3(0.7, 0.35, 0.1),
+        dark_filament: glm::Vec3 = glm::vec3(0.35, 0.15, 0.06),
+        granulation_scale: f32 = 14.0,
+        granulation_mix: f32 = 0.35,
+        filament_scale: f32 = 2.5,
+        filament_strength: f32 = 0.55,
+        pulse_amount: f32 = 0.07,
+        pulse_speed: f32 = 2.3,
+    }
+}
 
-    let core = glm::vec3(1.05, 1.00, 0.94);
-    let mid = glm::vec3(1.00, 0.82, 0.35);
-    let corona = glm::vec3(1.00, 0.48, 0.08);
-    let halo = glm::vec3(1.00, 0.90, 0.60);
+fn shade_star(p: &StarParams, f: &Fragment, center: Vec3, radius: f32, time: f32) -> Color {
+    let local = f.p - center;
+    let r_len = local.magnitude().max(1e-5);
+    let r_norm = saturate(r_len / radius);
 
     let mut t = r_norm * 1.2;
     t = saturate(t);
-    let mut col = lerp(core, mid, t);
+    let mut col = lerp(p.core, p.mid, t);
 
     let t2 = saturate((r_norm - 0.65) / 0.4);
-    col = lerp(col, corona, t2 * 0.8);
+    col = lerp(col, p.corona, t2 * 0.8);
 
-    let gran_scale = 30.0;
-    let g1 = (lat * gran_scale + time * 3.0).sin();
-    let g2 = (lon * gran_scale * 1.2 - time * 2.1).cos();
-    let g3 = ((lat * 0.7 + lon * 1.3) * gran_scale * 0.25 + time * 1.7).sin();
-    let gran = (g1 * 0.45 + g2 * 0.35 + g3 * 0.20).clamp(-1.0, 1.0);
+    // Granulación: celdas de Worley (bordes oscuros) que burbujean con el tiempo
+    let noise = SURFACE_NOISE.derive(1);
+    let d = local / r_len;
+    let boil = glm::vec3(time * 0.11, -time * 0.07, time * 0.05);
+    let (c1, c2) = noise.worley(d * p.granulation_scale + boil);
+    let fine = noise.simplex(d * (p.granulation_scale * 40.0 / 14.0) + boil);
+    let gran = (saturate((c2 - c1) * 2.5) * 2.0 - 1.0) * 0.8 + fine * 0.2;
 
-    let bright_cell = glm::vec3(1.3, 1.15, 0.7);
-    let dark_cell = glm::vec3(0.7, 0.35, 0.1);
     let gran_t = 0.5 + 0.5 * gran;
-    let gran_color = lerp(dark_cell, bright_cell, gran_t);
+    let gran_color = lerp(p.dark_cell, p.bright_cell, gran_t);
 
-    col = col * 0.65 + gran_color * 0.35;
+    col = col * (1.0 - p.granulation_mix) + gran_color * p.granulation_mix;
 
-    let fil_scale = 6.0;
-    let f1 = (lat * fil_scale + time * 0.7).sin();
-    let f2 = (lon * fil_scale * 1.4 - time * 0.9).cos();
-    let fil = (f1 * 0.6 + f2 * 0.4).clamp(-1.0, 1.0);
-    let fil_intensity = saturate((fil - 0.1) * 2.0);
-    let dark_fil = glm::vec3(0.35, 0.15, 0.06);
-    col = col * (1.0 - fil_intensity * 0.55) + dark_fil * (fil_intensity * 0.55);
+    // Filamentos: fBm deformado que deriva lentamente
+    let drift = glm::vec3(-time * 0.04, time * 0.03, time * 0.05);
+    let q = domain_warp(d * p.filament_scale + drift, 0.6, |x| noise.simplex(x));
+    let fil = fbm(q, &Fractal::octaves(4), |x| noise.simplex(x)) * 2.0;
+    let fil_intensity = saturate((fil - 0.1) * 2.0) * p.filament_strength;
+    col = col * (1.0 - fil_intensity) + p.dark_filament * fil_intensity;
 
     let edge = saturate((r_norm - 0.8) / 0.25);
     let halo_factor = edge * edge;
-    col = col * (1.0 + halo_factor * 0.4) + halo * (halo_factor * 0.3);
+    col = col * (1.0 + halo_factor * 0.4) + p.halo * (halo_factor * 0.3);
 
-    let facing = (-n_view.z).max(0.0);
+    let facing = (-f.n_view.z).max(0.0);
     let view_brightness = 0.5 + 0.6 * facing;
     col *= view_brightness;
 
-    let pulse = 0.93 + 0.07 * (time * 2.3).sin();
+    let pulse = 1.0 - p.pulse_amount + p.pulse_amount * (time * p.pulse_speed).sin();
     col *= pulse;
 
     let r = (col.x.clamp(0.0, 1.4) * 255.0) as u8;
@@ -103,52 +284,83 @@ fn shade_star(p: Vec3, n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Co
 // ----- TIERRA (ROCKY) -----
 //
 
-fn shade_rocky_earth(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Color {
-    let local = p - center;
-    let (lat, lon, r_len) = spherical_coords(local);
-    let r_norm = (r_len / radius).min(1.0);
-
-    let ocean = glm::vec3(0.02, 0.12, 0.40);
-    let coast = glm::vec3(0.18, 0.42, 0.27);
-    let land = glm::vec3(0.32, 0.55, 0.22);
-    let desert = glm::vec3(0.74, 0.60, 0.38);
-    let mountain = glm::vec3(0.78, 0.78, 0.82);
-    let ice = glm::vec3(0.93, 0.97, 1.0);
+params_struct! {
+    pub struct EarthParams {
+        ocean: glm::Vec3 = glm::vec3(0.02, 0.12, 0.40),
+        coast: glm::Vec3 = glm::vec3(0.18, 0.42, 0.27),
+        land: glm::Vec3 = glm::vec3(0.32, 0.55, 0.22),
+        desert: glm::Vec3 = glm::vec3(0.74, 0.60, 0.38),
+        mountain: glm::Vec3 = glm::vec3(0.78, 0.78, 0.82),
+        ice: glm::Vec3 = glm::vec3(0.93, 0.97, 1.0),
+        city_color: glm::Vec3 = glm::vec3(1.0, 0.78, 0.40),
+        // frecuencia y deformación de los continentes
+        continent_scale: f32 = 1.6,
+        continent_warp: f32 = 0.45,
+        // frecuencia y altura de las cordilleras
+        mountain_scale: f32 = 3.0,
+        mountain_height: f32 = 0.45,
+        // exponente de la latitud en los casquetes (mayor = casquetes más pequeños)
+        ice_cap_power: f32 = 3.0,
+        city_intensity: f32 = 0.9,
+    }
+}
 
-    let n1 = (lon * 3.0 + (lat * 2.0).sin() * 1.3).sin();
-    let n2 = (lon * 7.0 + lat * 5.0).cos();
-    let n3 = ((lon * 11.0 + time * 0.25).sin() * (lat * 6.0).cos()) * 0.4;
+fn shade_rocky_earth(
+    p: &EarthParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    _time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
+    let (lat, _, r_len) = spherical_coords(local);
+    let r_norm = (r_len / radius).min(1.0);
 
-    let mut h = 0.55 * n1 + 0.35 * n2 + 0.25 * n3;
-    h = h.clamp(-1.0, 1.0);
+    // Continentes: fBm sobre un dominio deformado (costas irregulares) y
+    // cordilleras ridged solo tierra adentro
+    let noise = SURFACE_NOISE.derive(2);
+    let d = local / r_len;
+    let q = domain_warp(d * p.continent_scale, p.continent_warp, |x| noise.simplex(x));
+    let continents = fbm(q, &Fractal::octaves(5), |x| noise.simplex(x)) * 2.2;
+    let ranges = ridged(d * p.mountain_scale, &Fractal::octaves(4), |x| {
+        noise.derive(1).simplex(x)
+    });
+    let h = (continents + ranges * p.mountain_height * saturate(continents * 2.0)).clamp(-1.0, 1.0);
 
     let mut col: glm::Vec3;
     if h < -0.15 {
-        col = ocean;
+        col = p.ocean;
     } else if h < 0.20 {
         let t = (h + 0.15) / 0.35;
         let t = t.clamp(0.0, 1.0);
-        col = ocean * (1.0 - t) + coast * t;
+        col = p.ocean * (1.0 - t) + p.coast * t;
     } else if h < 0.45 {
         let t = (h - 0.20) / 0.25;
         let t = t.clamp(0.0, 1.0);
-        col = coast * (1.0 - t) + land * t;
+        col = p.coast * (1.0 - t) + p.land * t;
     } else if h < 0.75 {
         let t = (h - 0.45) / 0.30;
         let t = t.clamp(0.0, 1.0);
-        col = land * (1.0 - t) + desert * t;
+        col = p.land * (1.0 - t) + p.desert * t;
     } else {
         let t = (h - 0.75) / 0.25;
         let t = t.clamp(0.0, 1.0);
-        col = desert * (1.0 - t) + mountain * t;
+        col = p.desert * (1.0 - t) + p.mountain * t;
     }
 
-    let poles = (lat.abs() / (std::f32::consts::FRAC_PI_2)).powf(3.0);
+    let poles = (lat.abs() / (std::f32::consts::FRAC_PI_2)).powf(p.ice_cap_power);
     let ice_factor = poles.clamp(0.0, 1.0);
-    col = col * (1.0 - ice_factor) + ice * ice_factor;
+    col = col * (1.0 - ice_factor) + p.ice * ice_factor;
 
     let limb = 0.55 + 0.45 * (1.0 - r_norm * r_norm).max(0.0);
     col *= limb;
+    col *= sun_light(f.n, sun);
+
+    // Ciudades en tierra firme (ni océano ni hielo), solo en el lado nocturno
+    let land = saturate((h - 0.15) * 6.0) * (1.0 - ice_factor);
+    let night = night_amount(f.n, sun);
+    col += p.city_color * (city_lights(d, land) * night * p.city_intensity);
 
     let r = (col.x.clamp(0.0, 1.0) * 255.0) as u8;
     let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
@@ -160,46 +372,73 @@ fn shade_rocky_earth(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f3
 // ----- MERCURIO -----
 //
 
-fn shade_mercury(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Color {
-    let local = p - center;
+params_struct! {
+    pub struct MercuryParams {
+        base_dark: glm::Vec3 = glm::vec3(0.25, 0.20, 0.18),
+        base_mid: glm::Vec3 = glm::vec3(0.40, 0.32, 0.26),
+        base_bright: glm::Vec3 = glm::vec3(0.65, 0.55, 0.42),
+        grain_color: glm::Vec3 = glm::vec3(0.10, 0.08, 0.06),
+        pit_color: glm::Vec3 = glm::vec3(0.17, 0.15, 0.15),
+        crater_color: glm::Vec3 = glm::vec3(0.15, 0.14, 0.14),
+        grain_scale: f32 = 6.0,
+        pit_scale: f32 = 9.0,
+        // intensidad del relieve (grano, hoyos y cráteres) en la iluminación
+        bump_strength: f32 = 1.0,
+    }
+}
+
+// Cráteres grandes de Mercurio (lat, lon en radianes)
+const MERCURY_CRATERS: [(f32, f32); 4] = [(0.25, 0.8), (-0.10, -0.7), (0.05, 2.4), (-0.35, 1.5)];
+
+// Relieve: grano de fBm, hoyos en los centros de Worley y cuencos de los cráteres
+fn mercury_height(p: &MercuryParams, noise: Noise, d: Vec3) -> f32 {
+    let grain = fbm(d * p.grain_scale, &Fractal::octaves(5), |x| noise.simplex(x));
+    let (c1, _) = noise.worley(d * p.pit_scale);
+    let pit = saturate(1.0 - c1 * 3.0);
+    let (lat, lon, _) = spherical_coords(d);
+    let craters: f32 = MERCURY_CRATERS
+        .iter()
+        .map(|&(c_lat, c_lon)| (-crater_distance2(lat, lon, c_lat, c_lon) * 130.0).exp())
+        .sum();
+    grain * 0.012 - pit * pit * 0.004 - craters * 0.012
+}
+
+fn shade_mercury(
+    p: &MercuryParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    _time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
     let (lat, lon, r_len) = spherical_coords(local);
     let r_norm = (r_len / radius).min(1.0);
 
-    let base_dark = glm::vec3(0.25, 0.20, 0.18);
-    let base_mid = glm::vec3(0.40, 0.32, 0.26);
-    let base_bright = glm::vec3(0.65, 0.55, 0.42);
+    let heat = saturate(glm::dot(&f.n, &sun.dir) * 0.6 + 0.4);
 
-    let sun_dir = glm::vec3(1.0, 0.2, 0.0);
-    let n = glm::vec3(local.x, local.y, local.z).normalize();
-    let heat = saturate(glm::dot(&n, &sun_dir) * 0.6 + 0.4);
+    let mut col = lerp(p.base_dark, p.base_mid, heat);
+    col = lerp(col, p.base_bright, heat * 0.5);
 
-    let mut col = lerp(base_dark, base_mid, heat);
-    col = lerp(col, base_bright, heat * 0.5);
+    let noise = SURFACE_NOISE.derive(4);
+    let d = local / r_len;
+    let grain = fbm(d * p.grain_scale, &Fractal::octaves(5), |x| noise.simplex(x)) * 2.2;
+    col += p.grain_color * grain.clamp(-1.0, 1.0);
 
-    let n1 = cheap_noise(lat * 7.0, lon * 9.0, time * 0.15, 12.0);
-    let n2 = cheap_noise(lat * 15.0, lon * 18.0, time * 0.07, 25.0);
-    let grain = (n1 * 0.7 + n2 * 0.3).clamp(-1.0, 1.0);
-    col += grain * glm::vec3(0.10, 0.08, 0.06);
+    // Cráteres pequeños: centro de cada celda de Worley
+    let (c1, _) = noise.worley(d * p.pit_scale);
+    let pit = saturate(1.0 - c1 * 3.0);
+    col = lerp(col, p.pit_color, pit * pit * 0.6);
 
-    let mut crater_dark = glm::vec3(0.18, 0.16, 0.16);
-    let craters = [
-        (0.25_f32, 0.8_f32),
-        (-0.10, -0.7),
-        (0.05, 2.4),
-        (-0.35, 1.5),
-    ];
-    for (c_lat, c_lon) in craters {
-        let d_lat = lat - c_lat;
-        let d_lon = (lon - c_lon + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI)
-            - std::f32::consts::PI;
-        let d2 = d_lat * d_lat + d_lon * d_lon;
-        let crater = (-d2 * 130.0).exp();
-        crater_dark = glm::vec3(0.15, 0.14, 0.14);
-        col = col * (1.0 - crater * 0.7) + crater_dark * (crater * 0.7);
+    for (c_lat, c_lon) in MERCURY_CRATERS {
+        let crater = (-crater_distance2(lat, lon, c_lat, c_lon) * 130.0).exp();
+        col = col * (1.0 - crater * 0.7) + p.crater_color * (crater * 0.7);
     }
 
     let limb = 0.55 + 0.45 * (1.0 - r_norm * r_norm).max(0.0);
     col *= limb;
+    let n = bump_normal(f.n, d, p.bump_strength, |x| mercury_height(p, noise, x));
+    col *= sun_light(n, sun);
 
     let r = (col.x.clamp(0.0, 1.0) * 255.0) as u8;
     let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
@@ -211,34 +450,49 @@ fn shade_mercury(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -
 // ----- VENUS -----
 //
 
-fn shade_venus(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Color {
-    let local = p - center;
+params_struct! {
+    pub struct VenusParams {
+        deep_cloud: glm::Vec3 = glm::vec3(0.80, 0.65, 0.30),
+        mid_cloud: glm::Vec3 = glm::vec3(0.95, 0.80, 0.45),
+        high_cloud: glm::Vec3 = glm::vec3(1.00, 0.92, 0.70),
+        swirl_color: glm::Vec3 = glm::vec3(1.05, 0.90, 0.55),
+        highlight_color: glm::Vec3 = glm::vec3(1.15, 1.00, 0.80),
+        band_frequency: f32 = 6.0,
+        band_speed: f32 = 0.4,
+        swirl_strength: f32 = 0.35,
+    }
+}
+
+fn shade_venus(
+    p: &VenusParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
     let (lat, lon, r_len) = spherical_coords(local);
     let r_norm = (r_len / radius).min(1.0);
     let y = lat / std::f32::consts::FRAC_PI_2;
 
-    let deep_cloud = glm::vec3(0.80, 0.65, 0.30);
-    let mid_cloud = glm::vec3(0.95, 0.80, 0.45);
-    let high_cloud = glm::vec3(1.00, 0.92, 0.70);
-
     let t = (y * 0.4 + 0.5).clamp(0.0, 1.0);
-    let mut col = lerp(deep_cloud, mid_cloud, t);
-    col = lerp(col, high_cloud, 0.35);
+    let mut col = lerp(p.deep_cloud, p.mid_cloud, t);
+    col = lerp(col, p.high_cloud, 0.35);
 
-    let band1 = (y * 6.0 + time * 0.4).sin();
+    let band1 = (y * p.band_frequency + time * p.band_speed).sin();
     let band2 = (lon * 4.0 + y * 3.0 - time * 0.3).cos();
     let swirl = (band1 * 0.7 + band2 * 0.3).clamp(-1.0, 1.0);
-    let swirl_color = glm::vec3(1.05, 0.90, 0.55);
-    let swirl_t = 0.5 + 0.5 * swirl;
-    col = col * (1.0 - swirl_t * 0.35) + swirl_color * (swirl_t * 0.35);
+    let swirl_t = (0.5 + 0.5 * swirl) * p.swirl_strength;
+    col = col * (1.0 - swirl_t) + p.swirl_color * swirl_t;
 
     let long_wave = (lon * 2.0 - time * 0.2).sin();
     let highlight = saturate((long_wave * 0.5 + 0.5) * (1.0 - y.abs()));
-    let high_light_color = glm::vec3(1.15, 1.00, 0.80);
-    col = col * (1.0 - highlight * 0.25) + high_light_color * (highlight * 0.4);
+    col = col * (1.0 - highlight * 0.25) + p.highlight_color * (highlight * 0.4);
 
     let limb = 0.70 + 0.30 * (1.0 - r_norm * r_norm).max(0.0);
     col *= limb;
+    col *= sun_light(f.n, sun);
 
     let r = (col.x.clamp(0.0, 1.3) * 255.0) as u8;
     let g = (col.y.clamp(0.0, 1.2) * 255.0) as u8;
@@ -250,47 +504,81 @@ fn shade_venus(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) ->
 // ----- MARTE -----
 //
 
-fn shade_mars(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Color {
-    let local = p - center;
-    let (lat, lon, r_len) = spherical_coords(local);
+params_struct! {
+    pub struct MarsParams {
+        low_plain: glm::Vec3 = glm::vec3(0.45, 0.18, 0.10),
+        mid_plain: glm::Vec3 = glm::vec3(0.66, 0.26, 0.14),
+        high_plain: glm::Vec3 = glm::vec3(0.78, 0.38, 0.20),
+        dark_color: glm::Vec3 = glm::vec3(0.22, 0.10, 0.08),
+        dust_storm: glm::Vec3 = glm::vec3(0.90, 0.55, 0.30),
+        polar_ice: glm::Vec3 = glm::vec3(0.96, 0.95, 0.92),
+        terrain_scale: f32 = 2.0,
+        dark_scale: f32 = 1.3,
+        storm_scale: f32 = 3.0,
+        // umbral del ruido de tormentas (mayor = menos tormentas)
+        storm_threshold: f32 = 0.4,
+        // velocidad de las tormentas (rad/s aprox. sobre la esfera unidad)
+        wind: glm::Vec3 = glm::vec3(0.05, 0.0, -0.04),
+        polar_cap_power: f32 = 3.0,
+        // intensidad del relieve de las llanuras en la iluminación
+        bump_strength: f32 = 1.0,
+    }
+}
+
+// Altura de las llanuras en [-1, 1]: decide su color (bajas, medias y altas) y,
+// escalada, su relieve
+fn mars_plains(p: &MarsParams, noise: Noise, d: Vec3) -> f32 {
+    let h = fbm(d * p.terrain_scale, &Fractal::octaves(5), |x| noise.simplex(x)) * 2.0;
+    h.clamp(-1.0, 1.0)
+}
+
+fn shade_mars(
+    p: &MarsParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
+    let (lat, _, r_len) = spherical_coords(local);
     let r_norm = (r_len / radius).min(1.0);
     let y = lat / std::f32::consts::FRAC_PI_2;
 
-    let low_plain = glm::vec3(0.45, 0.18, 0.10);
-    let mid_plain = glm::vec3(0.66, 0.26, 0.14);
-    let high_plain = glm::vec3(0.78, 0.38, 0.20);
-    let dust_storm = glm::vec3(0.90, 0.55, 0.30);
-    let polar_ice = glm::vec3(0.96, 0.95, 0.92);
-
-    let n1 = cheap_noise(lat * 3.0, lon * 4.0, time * 0.1, 10.0);
-    let n2 = cheap_noise(lat * 6.0, lon * 7.0, time * 0.2, 18.0);
-    let h = (0.6 * n1 + 0.4 * n2).clamp(-1.0, 1.0);
+    let noise = SURFACE_NOISE.derive(5);
+    let d = local / r_len;
+    let h = mars_plains(p, noise, d);
 
     let mut col = if h < -0.2 {
-        low_plain
+        p.low_plain
     } else if h < 0.3 {
         let t = (h + 0.2) / 0.5;
-        lerp(low_plain, mid_plain, t.clamp(0.0, 1.0))
+        lerp(p.low_plain, p.mid_plain, t.clamp(0.0, 1.0))
     } else {
         let t = (h - 0.3) / 0.7;
-        lerp(mid_plain, high_plain, t.clamp(0.0, 1.0))
+        lerp(p.mid_plain, p.high_plain, t.clamp(0.0, 1.0))
     };
 
-    let dark_map = (lon * 5.0 - time * 0.15).sin() * (y * 3.0).cos();
+    let dark_noise = noise.derive(1);
+    let dark_map = fbm(d * p.dark_scale, &Fractal::octaves(3), |x| dark_noise.perlin(x)) * 2.5;
     let dark_t = saturate((dark_map - 0.2) * 2.5);
-    let dark_color = glm::vec3(0.22, 0.10, 0.08);
-    col = col * (1.0 - dark_t * 0.5) + dark_color * (dark_t * 0.5);
+    col = col * (1.0 - dark_t * 0.5) + p.dark_color * (dark_t * 0.5);
 
-    let storm_noise = cheap_noise(lat * 9.0, lon * 10.0, time * 0.5, 20.0);
-    let storm_mask = saturate((storm_noise - 0.4) * 2.0);
-    col = col * (1.0 - storm_mask * 0.4) + dust_storm * (storm_mask * 0.6);
+    // Tormentas de polvo: fBm deformado que se desplaza con el tiempo
+    let storm_noise = noise.derive(2);
+    let q = domain_warp(d * p.storm_scale + p.wind * time, 0.8, |x| storm_noise.simplex(x));
+    let storm = fbm(q, &Fractal::octaves(4), |x| storm_noise.simplex(x)) * 2.0;
+    let storm_mask = saturate((storm - p.storm_threshold) * 2.0);
+    col = col * (1.0 - storm_mask * 0.4) + p.dust_storm * (storm_mask * 0.6);
 
-    let polar = y.abs().powf(3.0);
+    let polar = y.abs().powf(p.polar_cap_power);
     let polar_mask = saturate(polar * 1.8);
-    col = col * (1.0 - polar_mask) + polar_ice * polar_mask;
+    col = col * (1.0 - polar_mask) + p.polar_ice * polar_mask;
 
     let limb = 0.55 + 0.45 * (1.0 - r_norm * r_norm).max(0.0);
     col *= limb;
+    let n = bump_normal(f.n, d, p.bump_strength, |x| mars_plains(p, noise, x) * 0.03);
+    col *= sun_light(n, sun);
 
     let r = (col.x.clamp(0.0, 1.1) * 255.0) as u8;
     let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
@@ -302,52 +590,80 @@ fn shade_mars(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -> C
 // ----- JÚPITER (GAS GIANT) -----
 //
 
-fn shade_gas_giant_jupiter(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, time: f32) -> Color {
-    let local = p - center;
+params_struct! {
+    pub struct JupiterParams {
+        eq_light: glm::Vec3 = glm::vec3(0.94, 0.86, 0.68),
+        eq_dark: glm::Vec3 = glm::vec3(0.83, 0.64, 0.44),
+        mid1: glm::Vec3 = glm::vec3(0.78, 0.58, 0.40),
+        mid2: glm::Vec3 = glm::vec3(0.72, 0.50, 0.34),
+        high: glm::Vec3 = glm::vec3(0.80, 0.72, 0.60),
+        stripe_color: glm::Vec3 = glm::vec3(1.05, 0.90, 0.70),
+        spot_color: glm::Vec3 = glm::vec3(1.05, 0.58, 0.32),
+        vortex_color: glm::Vec3 = glm::vec3(0.98, 0.78, 0.52),
+        haze_color: glm::Vec3 = glm::vec3(0.98, 0.92, 0.80),
+        line_color: glm::Vec3 = glm::vec3(0.40, 0.85, 1.15),
+        lightning_color: glm::Vec3 = glm::vec3(0.80, 0.90, 1.00),
+        band_frequency: f32 = 5.0,
+        band_speed: f32 = 0.25,
+        stripe_strength: f32 = 0.15,
+        // latitud (rad) y velocidad en longitud de la gran mancha
+        spot_lat: f32 = -0.22,
+        spot_speed: f32 = 0.45,
+        lightning_intensity: f32 = 1.3,
+    }
+}
+
+fn shade_gas_giant_jupiter(
+    p: &JupiterParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
     let (lat, lon, r_len) = spherical_coords(local);
     let r_norm = saturate(r_len / radius);
 
-    let base_eq_light = glm::vec3(0.94, 0.86, 0.68);
-    let base_eq_dark = glm::vec3(0.83, 0.64, 0.44);
-    let base_mid1 = glm::vec3(0.78, 0.58, 0.40);
-    let base_mid2 = glm::vec3(0.72, 0.50, 0.34);
-    let base_high = glm::vec3(0.80, 0.72, 0.60);
-
     let y = lat / std::f32::consts::FRAC_PI_2;
 
-    let band_pattern = (y * 5.0 + 0.4 * (lon * 1.5).sin() + time * 0.25).sin();
+    let noise = SURFACE_NOISE.derive(6);
+    let d = local / r_len;
+    let band_warp = fbm(d * 2.0, &Fractal::octaves(3), |x| noise.simplex(x)) * 0.8;
+    let band_pattern = (y * p.band_frequency + band_warp + time * p.band_speed).sin();
     let band_mix = 0.5 + 0.5 * band_pattern;
 
     let mut col = if band_mix < 0.25 {
         let t = band_mix / 0.25;
-        lerp(base_high, base_mid2, t)
+        lerp(p.high, p.mid2, t)
     } else if band_mix < 0.5 {
         let t = (band_mix - 0.25) / 0.25;
-        lerp(base_mid2, base_mid1, t)
+        lerp(p.mid2, p.mid1, t)
     } else if band_mix < 0.75 {
         let t = (band_mix - 0.5) / 0.25;
-        lerp(base_mid1, base_eq_dark, t)
+        lerp(p.mid1, p.eq_dark, t)
     } else {
         let t = (band_mix - 0.75) / 0.25;
-        lerp(base_eq_dark, base_eq_light, t)
+        lerp(p.eq_dark, p.eq_light, t)
     };
 
-    let stripe_noise = cheap_noise(lat * 4.0, lon * 8.0 + time * 1.2, time * 0.5, 15.0);
-    let stripe_intensity = 0.15 * stripe_noise;
-    let high_stripe_color = glm::vec3(1.05, 0.90, 0.70);
-    col = col * (1.0 - stripe_intensity) + high_stripe_color * stripe_intensity;
-
-    let spot_lat = -0.22;
-    let spot_lon = time * 0.45;
-    let d_lat = lat - spot_lat;
+    // Turbulencia estirada en latitud: vetas finas a lo largo de las bandas
+    let flow = glm::vec3(time * 0.08, 0.0, time * 0.05);
+    let streaks = glm::vec3(d.x * 3.0, d.y * 16.0, d.z * 3.0) + flow;
+    let stripe_noise = turbulence(streaks, &Fractal::octaves(4), |x| noise.derive(1).simplex(x))
+        * 4.0
+        - 1.0;
+    let stripe_intensity = p.stripe_strength * stripe_noise;
+    col = col * (1.0 - stripe_intensity) + p.stripe_color * stripe_intensity;
+
+    let spot_lon = time * p.spot_speed;
+    let d_lat = lat - p.spot_lat;
     let d_lon = (lon - spot_lon + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI)
         - std::f32::consts::PI;
     let d2 = d_lat * d_lat + d_lon * d_lon;
     let big_spot_strength = (-d2 * 7.0).exp();
-    let big_spot_color = glm::vec3(1.05, 0.58, 0.32);
-    col = col * (1.0 - big_spot_strength) + big_spot_color * big_spot_strength;
+    col = col * (1.0 - big_spot_strength) + p.spot_color * big_spot_strength;
 
-    let mut vortex_color = glm::vec3(0.95, 0.70, 0.45);
     let vortices = [(0.35, 1.2), (-0.05, 2.7), (0.10, -1.5), (-0.30, -2.2)];
     for (v_lat, v_lon_offset) in vortices {
         let d_lat_v = lat - v_lat;
@@ -357,15 +673,12 @@ fn shade_gas_giant_jupiter(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, ti
             - std::f32::consts::PI;
         let d2_v = d_lat_v * d_lat_v + d_lon_v * d_lon_v;
         let vortex_strength = (-d2_v * 18.0).exp();
-        vortex_color = glm::vec3(0.98, 0.78, 0.52);
-        col = col * (1.0 - vortex_strength * 0.7) + vortex_color * (vortex_strength * 0.7);
+        col = col * (1.0 - vortex_strength * 0.7) + p.vortex_color * (vortex_strength * 0.7);
     }
 
     let haze = (1.0 - y.abs()).powf(2.0);
-    let haze_color = glm::vec3(0.98, 0.92, 0.80);
-    col = col * (1.0 - haze * 0.25) + haze_color * (haze * 0.25);
+    col = col * (1.0 - haze * 0.25) + p.haze_color * (haze * 0.25);
 
-    let cyan = glm::vec3(0.40, 0.85, 1.15);
     let line_pattern =
         (lon * 20.0 + y * 6.0 - time * 2.0).sin() + (lon * 7.0 - y * 10.0 + time * 1.3).cos() * 0.5;
 
@@ -374,14 +687,19 @@ fn shade_gas_giant_jupiter(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, ti
     let equator_boost = (1.0 - y.abs()).powf(2.0);
     let line_strength = line_mask * equator_boost;
 
-    col = col * (1.0 - line_strength * 0.5) + cyan * (line_strength * 0.9);
+    col = col * (1.0 - line_strength * 0.5) + p.line_color * (line_strength * 0.9);
 
     let limb = 0.55 + 0.45 * (1.0 - r_norm * r_norm).max(0.0);
     col *= limb;
+    col *= sun_light(f.n, sun);
 
     let breathe = 0.96 + 0.04 * (time * 0.8).sin();
     col *= breathe;
 
+    // Relámpagos entre las nubes: solo se distinguen del lado nocturno
+    let flash = lightning(d, time) * night_amount(f.n, sun);
+    col += p.lightning_color * (flash * p.lightning_intensity);
+
     let r = (col.x.clamp(0.0, 1.4) * 255.0) as u8;
     let g = (col.y.clamp(0.0, 1.3) * 255.0) as u8;
     let b = (col.z.clamp(0.0, 1.4) * 255.0) as u8;
@@ -392,43 +710,461 @@ fn shade_gas_giant_jupiter(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, ti
 // ----- LUNA -----
 //
 
-fn shade_moon(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, _time: f32) -> Color {
-    let local = p - center;
+params_struct! {
+    pub struct MoonParams {
+        base: glm::Vec3 = glm::vec3(0.42, 0.42, 0.45),
+        grit_color: glm::Vec3 = glm::vec3(0.10, 0.10, 0.12),
+        crater_floor: glm::Vec3 = glm::vec3(0.22, 0.22, 0.24),
+        crater_rim: glm::Vec3 = glm::vec3(0.85, 0.85, 0.90),
+        small_floor: glm::Vec3 = glm::vec3(0.24, 0.24, 0.26),
+        small_rim: glm::Vec3 = glm::vec3(0.70, 0.70, 0.74),
+        grit_scale: f32 = 5.0,
+        small_crater_scale: f32 = 7.0,
+        // intensidad del relieve (cráteres con borde elevado) en la iluminación
+        bump_strength: f32 = 1.0,
+    }
+}
+
+// Cráteres grandes de la Luna (lat, lon, radio del borde; en radianes)
+const MOON_CRATERS: [(f32, f32, f32); 4] =
+    [(0.10, 0.20, 0.22), (-0.30, -1.0, 0.28), (0.35, 1.7, 0.20), (-0.10, 2.5, 0.18)];
+
+// Relieve: polvo de fBm, cráteres menores de Worley y cráteres grandes, todos con
+// fondo hundido y borde elevado
+fn moon_height(p: &MoonParams, noise: Noise, d: Vec3) -> f32 {
+    let grit = fbm(d * p.grit_scale, &Fractal::octaves(5), |x| noise.simplex(x));
+    let (c1, _) = noise.worley(d * p.small_crater_scale);
+    let floor = saturate(1.0 - c1 * 3.5);
+    let small_rim = (-((c1 - 0.3) / 0.06).powi(2)).exp();
+    let (lat, lon, _) = spherical_coords(d);
+    let mut h = grit * 0.010 - floor * floor * 0.008 + small_rim * 0.002;
+    for (c_lat, c_lon, r0) in MOON_CRATERS {
+        let dist = crater_distance2(lat, lon, c_lat, c_lon).sqrt();
+        let bowl = 1.0 - smoothstep(0.0, r0, dist);
+        let rim = (-(dist - r0).powi(2) * 220.0).exp();
+        h += -bowl * bowl * 0.03 + rim * 0.008;
+    }
+    h
+}
+
+fn shade_moon(
+    p: &MoonParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    _time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
     let (lat, lon, r_len) = spherical_coords(local);
     let r_norm = (r_len / radius).min(1.0);
 
-    let base = glm::vec3(0.42, 0.42, 0.45);
+    let noise = SURFACE_NOISE.derive(7);
+    let d = local / r_len;
+    let grit = fbm(d * p.grit_scale, &Fractal::octaves(5), |x| noise.simplex(x)) * 2.2;
+    let mut col = p.base + p.grit_color * grit.clamp(-1.0, 1.0);
 
-    let n1 = cheap_noise(lat * 6.0, lon * 6.0, 0.0, 12.0);
-    let n2 = cheap_noise(lat * 11.0, lon * 13.0, 0.0, 22.0);
-    let grit = (n1 * 0.6 + n2 * 0.4).clamp(-1.0, 1.0);
-    let mut col = base + grit * glm::vec3(0.10, 0.10, 0.12);
+    // Cráteres menores: fondo oscuro y borde claro alrededor de cada celda de Worley
+    let (c1, _) = noise.worley(d * p.small_crater_scale);
+    let floor = saturate(1.0 - c1 * 3.5);
+    let small_rim = (-((c1 - 0.3) / 0.06).powi(2)).exp();
+    col = lerp(col, p.small_floor, floor * floor * 0.5);
+    col = lerp(col, p.small_rim, small_rim * 0.25);
 
-    let craters = [
-        (0.10_f32, 0.20_f32, 0.22_f32),
-        (-0.30, -1.0, 0.28),
-        (0.35, 1.7, 0.20),
-        (-0.10, 2.5, 0.18),
-    ];
+    for (c_lat, c_lon, r0) in MOON_CRATERS {
+        let dist = crater_distance2(lat, lon, c_lat, c_lon).sqrt();
+
+        let inner = (-dist * 45.0).exp();
+        let rim = (-(dist - r0).powi(2) * 220.0).exp();
+
+        col = col * (1.0 - inner * 0.6) + p.crater_floor * (inner * 0.6);
+        col = col * (1.0 - rim * 0.7) + p.crater_rim * (rim * 0.7);
+    }
+
+    let limb = 0.50 + 0.50 * (1.0 - r_norm * r_norm).max(0.0);
+    col *= limb;
+    let n = bump_normal(f.n, d, p.bump_strength, |x| moon_height(p, noise, x));
+    col *= sun_light(n, sun);
+
+    let r = (col.x.clamp(0.0, 1.0) * 255.0) as u8;
+    let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
+    let b = (col.z.clamp(0.0, 1.0) * 255.0) as u8;
+    Color::new(r, g, b, 255)
+}
+
+//
+// ----- IO (LUNA VOLCÁNICA) -----
+//
+
+params_struct! {
+    pub struct IoParams {
+        sulfur: glm::Vec3 = glm::vec3(0.93, 0.82, 0.35),
+        ochre: glm::Vec3 = glm::vec3(0.72, 0.50, 0.18),
+        frost: glm::Vec3 = glm::vec3(0.95, 0.93, 0.78),
+        caldera: glm::Vec3 = glm::vec3(0.16, 0.10, 0.06),
+        deposit: glm::Vec3 = glm::vec3(0.70, 0.26, 0.10),
+        lava_color: glm::Vec3 = glm::vec3(1.00, 0.38, 0.06),
+        terrain_scale: f32 = 3.0,
+        lava_intensity: f32 = 1.4,
+    }
+}
+
+fn shade_volcanic(
+    p: &IoParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
+    let (lat, lon, r_len) = spherical_coords(local);
+    let r_norm = (r_len / radius).min(1.0);
+    let y = lat / std::f32::consts::FRAC_PI_2;
 
-    for (c_lat, c_lon, r0) in craters {
-        let d_lat = lat - c_lat;
-        let d_lon = (lon - c_lon + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI)
+    let noise = SURFACE_NOISE.derive(8);
+    let d = local / r_len;
+    let h = fbm(d * p.terrain_scale, &Fractal::octaves(4), |x| noise.simplex(x)) * 2.0;
+    let mut col = lerp(p.ochre, p.sulfur, saturate(0.5 + h.clamp(-1.0, 1.0)));
+    col = lerp(col, p.frost, saturate(y.abs().powf(3.0) * 1.5));
+
+    // Volcanes: anillo rojizo de depósitos, caldera oscura y lava en el centro
+    let volcanoes = [
+        (0.20_f32, 0.50_f32),
+        (-0.35, 1.90),
+        (0.05, -2.20),
+        (0.45, -0.80),
+        (-0.15, 3.00),
+    ];
+    let mut lava = 0.0;
+    for (i, (v_lat, v_lon)) in volcanoes.into_iter().enumerate() {
+        let d_lat = lat - v_lat;
+        let d_lon = (lon - v_lon + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI)
             - std::f32::consts::PI;
-        let d = (d_lat * d_lat + d_lon * d_lon).sqrt();
+        let d2 = d_lat * d_lat + d_lon * d_lon;
+        col = lerp(col, p.deposit, (-d2 * 25.0).exp() * 0.35);
+        col = lerp(col, p.caldera, (-d2 * 90.0).exp() * 0.8);
+        let flicker = 0.75 + 0.25 * (time * (3.0 + i as f32) + i as f32).sin();
+        lava += (-d2 * 400.0).exp() * flicker;
+    }
+
+    let limb = 0.55 + 0.45 * (1.0 - r_norm * r_norm).max(0.0);
+    col *= limb;
+    col *= sun_light(f.n, sun);
+
+    // La lava brilla de día y de noche; de noche resalta más sobre la superficie oscura
+    col += p.lava_color * (saturate(lava) * p.lava_intensity);
+
+    let r = (col.x.clamp(0.0, 1.0) * 255.0) as u8;
+    let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
+    let b = (col.z.clamp(0.0, 1.0) * 255.0) as u8;
+    Color::new(r, g, b, 255)
+}
+
+//
+// ----- PLANETAS PROCEDURALES -----
+//
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Biome {
+    Rocky,
+    Icy,
+    Lava,
+    Gas,
+}
+
+impl Biome {
+    pub const ALL: [Biome; 4] = [Biome::Rocky, Biome::Icy, Biome::Lava, Biome::Gas];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Biome::Rocky => "Rocoso",
+            Biome::Icy => "Helado",
+            Biome::Lava => "Volcanico",
+            Biome::Gas => "Gaseoso",
+        }
+    }
+}
 
-        let inner = (-d * 45.0).exp();
-        let rim = (-(d - r0).powi(2) * 220.0).exp();
+// Colores de un bioma. En planetas sólidos: mar (o lava), llanura, tierras altas y
+// cumbres/tormentas; en gaseosos: tres familias de bandas y los óvalos de tormenta.
+#[derive(Copy, Clone, Debug)]
+pub struct Palette {
+    pub low: glm::Vec3,
+    pub mid: glm::Vec3,
+    pub high: glm::Vec3,
+    pub accent: glm::Vec3,
+}
 
-        let dark = glm::vec3(0.22, 0.22, 0.24);
-        let light_rim = glm::vec3(0.85, 0.85, 0.90);
+// Parámetros de un planeta procedural; el ruido del terreno sale de `seed`
+#[derive(Copy, Clone, Debug)]
+pub struct ProceduralParams {
+    pub seed: u32,
+    pub biome: Biome,
+    pub palette: Palette,
+    // altura (-1..1) bajo la cual hay mar, hielo marino o lava (no aplica a gaseosos)
+    pub sea_level: f32,
+    // 0 = sin cráteres, 1 = superficie saturada
+    pub crater_density: f32,
+    // número de bandas de polo a polo (gaseosos)
+    pub band_count: f32,
+    // alcance de los casquetes polares: 0 = sin casquetes, 1 = todo helado
+    pub ice_caps: f32,
+    // 0..1: frecuencia de tormentas (polvo, nieve, erupciones u óvalos)
+    pub storm_frequency: f32,
+}
 
-        col = col * (1.0 - inner * 0.6) + dark * (inner * 0.6);
-        col = col * (1.0 - rim * 0.7) + light_rim * (rim * 0.7);
+// Paletas base por bioma (low, mid, high, accent); la semilla elige una y la varía
+type PaletteDef = [[f32; 3]; 4];
+
+const ROCKY_PALETTES: [PaletteDef; 3] = [
+    // océano azul, vegetación, roca parda, nieve
+    [[0.03, 0.12, 0.35], [0.30, 0.48, 0.20], [0.55, 0.45, 0.32], [0.88, 0.88, 0.90]],
+    // mar verdoso, estepa ocre, roca rojiza, gris
+    [[0.05, 0.22, 0.25], [0.62, 0.50, 0.28], [0.55, 0.30, 0.20], [0.70, 0.68, 0.66]],
+    // desierto con mares poco profundos
+    [[0.10, 0.30, 0.40], [0.78, 0.64, 0.42], [0.62, 0.44, 0.30], [0.85, 0.80, 0.72]],
+];
+
+const ICY_PALETTES: [PaletteDef; 3] = [
+    [[0.55, 0.70, 0.82], [0.82, 0.89, 0.95], [0.95, 0.97, 1.00], [0.45, 0.58, 0.72]],
+    [[0.60, 0.62, 0.75], [0.85, 0.84, 0.90], [0.97, 0.96, 0.98], [0.55, 0.45, 0.55]],
+    [[0.50, 0.72, 0.70], [0.80, 0.92, 0.90], [0.96, 0.99, 0.98], [0.40, 0.55, 0.50]],
+];
+
+const LAVA_PALETTES: [PaletteDef; 3] = [
+    [[1.00, 0.35, 0.05], [0.16, 0.11, 0.09], [0.32, 0.24, 0.20], [1.00, 0.75, 0.25]],
+    [[1.00, 0.55, 0.10], [0.22, 0.16, 0.14], [0.40, 0.30, 0.26], [1.00, 0.90, 0.50]],
+    [[0.95, 0.20, 0.08], [0.10, 0.08, 0.08], [0.26, 0.22, 0.22], [1.00, 0.55, 0.20]],
+];
+
+const GAS_PALETTES: [PaletteDef; 3] = [
+    // joviano
+    [[0.72, 0.52, 0.36], [0.92, 0.84, 0.68], [0.62, 0.42, 0.30], [0.90, 0.42, 0.25]],
+    // tipo Neptuno
+    [[0.20, 0.38, 0.80], [0.45, 0.62, 0.92], [0.15, 0.25, 0.60], [0.85, 0.90, 1.00]],
+    // tipo Saturno
+    [[0.82, 0.74, 0.52], [0.95, 0.90, 0.72], [0.74, 0.64, 0.46], [0.98, 0.95, 0.85]],
+];
+
+impl ProceduralParams {
+    // Bioma y parámetros a partir de la semilla
+    pub fn from_seed(seed: u32) -> Self {
+        let rng = Noise::new(seed).derive(0x5eed);
+        let n = Biome::ALL.len();
+        let biome = Biome::ALL[(rng.random(0) * n as f32) as usize % n];
+        Self::with_biome(seed, biome)
     }
 
-    let limb = 0.50 + 0.50 * (1.0 - r_norm * r_norm).max(0.0);
+    // Parámetros plausibles para un bioma concreto; la semilla elige paleta y valores
+    pub fn with_biome(seed: u32, biome: Biome) -> Self {
+        let rng = Noise::new(seed).derive(0x5eed);
+        let range = |i: u32, lo: f32, hi: f32| lo + (hi - lo) * rng.random(i);
+
+        let palettes = match biome {
+            Biome::Rocky => &ROCKY_PALETTES,
+            Biome::Icy => &ICY_PALETTES,
+            Biome::Lava => &LAVA_PALETTES,
+            Biome::Gas => &GAS_PALETTES,
+        };
+        let base = palettes[(rng.random(1) * palettes.len() as f32) as usize % palettes.len()];
+        // pequeña variación de tono para que dos semillas con la misma paleta difieran
+        let shift = glm::vec3(range(2, -0.05, 0.05), range(3, -0.05, 0.05), range(4, -0.05, 0.05));
+        let color = |c: [f32; 3]| {
+            glm::vec3(
+                saturate(c[0] + shift.x),
+                saturate(c[1] + shift.y),
+                saturate(c[2] + shift.z),
+            )
+        };
+        let palette = Palette {
+            low: color(base[0]),
+            mid: color(base[1]),
+            high: color(base[2]),
+            accent: color(base[3]),
+        };
+
+        let (sea_level, crater_density, band_count, ice_caps, storm_frequency) = match biome {
+            Biome::Rocky => (
+                range(5, -0.35, 0.30),
+                range(6, 0.0, 0.25),
+                0.0,
+                range(8, 0.08, 0.30),
+                range(9, 0.0, 0.6),
+            ),
+            Biome::Icy => (
+                range(5, -0.6, 0.0),
+                range(6, 0.2, 0.8),
+                0.0,
+                range(8, 0.5, 1.0),
+                range(9, 0.0, 0.35),
+            ),
+            Biome::Lava => (
+                range(5, -0.45, 0.10),
+                range(6, 0.05, 0.45),
+                0.0,
+                0.0,
+                range(9, 0.2, 0.9),
+            ),
+            Biome::Gas => (
+                0.0,
+                0.0,
+                range(7, 4.0, 14.0).round(),
+                range(8, 0.0, 0.3),
+                range(9, 0.1, 0.8),
+            ),
+        };
+
+        Self {
+            seed,
+            biome,
+            palette,
+            sea_level,
+            crater_density,
+            band_count,
+            ice_caps,
+            storm_frequency,
+        }
+    }
+}
+
+// Superficie sólida: devuelve (albedo, emisión). Continentes con fBm deformado,
+// cordilleras ridged, cráteres de Worley, casquetes y tormentas.
+fn procedural_terrain(
+    p: &ProceduralParams,
+    noise: Noise,
+    d: Vec3,
+    time: f32,
+) -> (glm::Vec3, glm::Vec3) {
+    let pal = &p.palette;
+    let q = domain_warp(d * 1.5, 0.4, |x| noise.simplex(x));
+    let continents = fbm(q, &Fractal::octaves(5), |x| noise.simplex(x)) * 2.2;
+    let ranges = ridged(d * 3.5, &Fractal::octaves(4), |x| noise.derive(1).simplex(x));
+    let inland = saturate((continents - p.sea_level) * 3.0);
+    let h = (continents + ranges * 0.4 * inland).clamp(-1.0, 1.0);
+
+    let mut glow = glm::vec3(0.0, 0.0, 0.0);
+    let mut col = if h < p.sea_level {
+        // 0 en la costa, 1 en el fondo
+        let depth = saturate((p.sea_level - h) / (p.sea_level + 1.0).max(1e-3) * 2.5);
+        if p.biome == Biome::Lava {
+            // lagos de lava: costra oscura en la orilla, fundida hacia el centro
+            let molten = smoothstep(0.05, 0.6, depth);
+            let pulse = 0.85 + 0.15 * (time * 1.7 + noise.simplex(d * 6.0) * 4.0).sin();
+            glow = pal.low * (molten * pulse * 1.2);
+            lerp(pal.mid, pal.low * 0.5, molten)
+        } else {
+            lerp(pal.low, pal.low * 0.45, depth)
+        }
+    } else {
+        let t = saturate((h - p.sea_level) / (1.0 - p.sea_level).max(1e-3));
+        let land = lerp(pal.mid, pal.high, smoothstep(0.1, 0.6, t));
+        let peak = if p.biome == Biome::Lava { pal.high * 1.3 } else { pal.accent };
+        lerp(land, peak, smoothstep(0.65, 0.9, t))
+    };
+    let solid = smoothstep(p.sea_level, p.sea_level + 0.05, h);
+
+    // Cráteres: una celda de Worley por cráter; el ruido de valor decide qué celdas
+    // tienen uno, así la densidad no depende solo de la frecuencia
+    if p.crater_density > 0.0 {
+        let freq = 4.0 + 10.0 * p.crater_density;
+        let (c1, _) = noise.derive(2).worley(d * freq);
+        let present = 0.5 + 0.5 * noise.derive(3).value(d * freq * 0.7);
+        let threshold = 1.0 - p.crater_density;
+        let mask = smoothstep(threshold - 0.1, threshold + 0.1, present) * solid;
+        let floor = saturate(1.0 - c1 * 3.2);
+        let rim = (-((c1 - 0.32) / 0.06).powi(2)).exp();
+        col = lerp(col, col * 0.55, floor * floor * mask);
+        col = lerp(col, col * 1.35, rim * mask * 0.6);
+    }
+
+    // Tormentas: polvo o nieve en sólidos; nubes de ceniza con brasas en los de lava
+    if p.storm_frequency > 0.0 {
+        let wind = glm::vec3(time * 0.05, 0.0, -time * 0.04);
+        let storm_noise = noise.derive(5);
+        let q = domain_warp(d * 3.0 + wind, 0.8, |x| storm_noise.simplex(x));
+        let v = 0.5 + fbm(q, &Fractal::octaves(4), |x| storm_noise.simplex(x));
+        let threshold = 1.0 - 0.5 * p.storm_frequency;
+        let storm = smoothstep(threshold, threshold + 0.15, v);
+        if p.biome == Biome::Lava {
+            col = lerp(col, glm::vec3(0.12, 0.11, 0.10), storm * 0.7);
+            glow = lerp(glow, pal.accent * 0.5, storm * 0.5);
+        } else {
+            col = lerp(col, lerp(pal.high, glm::vec3(1.0, 1.0, 1.0), 0.5), storm * 0.6);
+        }
+    }
+
+    if p.ice_caps > 0.0 {
+        let edge = 1.0 - p.ice_caps;
+        let lat = d.y.abs() + noise.derive(4).simplex(d * 4.0) * 0.06;
+        let cap = smoothstep(edge - 0.03, edge + 0.03, lat);
+        col = lerp(col, glm::vec3(0.94, 0.96, 1.0), cap);
+        glow *= 1.0 - cap;
+    }
+
+    (col, glow)
+}
+
+// Gigante gaseoso: bandas deformadas, vetas de turbulencia y óvalos de tormenta
+fn procedural_gas(p: &ProceduralParams, noise: Noise, d: Vec3, time: f32) -> glm::Vec3 {
+    let pal = &p.palette;
+    let lat = d.y.clamp(-1.0, 1.0).asin();
+    let warp = fbm(d * 2.0, &Fractal::octaves(3), |x| noise.simplex(x)) * 0.6;
+    // `band_count` bandas de polo a polo, más una familia fina para romper la repetición
+    let band = 0.5 + 0.5 * (lat * p.band_count + warp).sin();
+    let fine = 0.5 + 0.5 * (lat * p.band_count * 2.3 + warp * 1.7 + 1.3).sin();
+    let mut col = lerp(pal.high, pal.mid, band);
+    col = lerp(col, pal.low, fine * 0.35);
+
+    let flow = glm::vec3(time * 0.06, 0.0, time * 0.04);
+    let streaks = glm::vec3(d.x * 3.0, d.y * 18.0, d.z * 3.0) + flow;
+    let t = turbulence(streaks, &Fractal::octaves(4), |x| noise.derive(1).simplex(x));
+    col = lerp(col, pal.mid * 1.1, saturate(t * 3.0 - 0.3) * 0.3);
+
+    // Óvalos: celdas de Worley aplastadas en latitud que derivan en longitud
+    if p.storm_frequency > 0.0 {
+        let (s, c) = (time * 0.05).sin_cos();
+        let r = glm::vec3(d.x * c - d.z * s, d.y, d.x * s + d.z * c);
+        let (c1, _) = noise.derive(2).worley(glm::vec3(r.x * 2.5, r.y * 7.0, r.z * 2.5));
+        let present = 0.5 + 0.5 * noise.derive(3).value(r * 2.5);
+        let threshold = 1.0 - p.storm_frequency;
+        let mask = smoothstep(threshold - 0.1, threshold + 0.1, present);
+        let oval = saturate(1.0 - c1 * 3.0);
+        col = lerp(col, pal.accent, oval * oval * mask);
+    }
+
+    // En gaseosos los "casquetes" son capuchas polares más oscuras
+    if p.ice_caps > 0.0 {
+        let hood = smoothstep(1.0 - p.ice_caps, 1.0, d.y.abs());
+        col = lerp(col, pal.low * 0.7, hood * 0.6);
+    }
+
+    col
+}
+
+fn shade_procedural(
+    p: &ProceduralParams,
+    f: &Fragment,
+    center: Vec3,
+    radius: f32,
+    time: f32,
+    sun: &SunLight,
+) -> Color {
+    let local = f.p - center;
+    let r_len = local.magnitude().max(1e-5);
+    let r_norm = (r_len / radius).min(1.0);
+    let d = local / r_len;
+    let noise = Noise::new(p.seed);
+
+    let (mut col, glow) = match p.biome {
+        Biome::Gas => (procedural_gas(p, noise, d, time), glm::vec3(0.0, 0.0, 0.0)),
+        _ => procedural_terrain(p, noise, d, time),
+    };
+
+    let limb = 0.55 + 0.45 * (1.0 - r_norm * r_norm).max(0.0);
     col *= limb;
+    col *= sun_light(f.n, sun);
+    col += glow;
 
     let r = (col.x.clamp(0.0, 1.0) * 255.0) as u8;
     let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
@@ -436,25 +1172,341 @@ fn shade_moon(p: Vec3, _n_view: Vec3, center: Vec3, radius: f32, _time: f32) ->
     Color::new(r, g, b, 255)
 }
 
+//
+// ----- ATMÓSFERA (CASCO TRANSLÚCIDO) -----
+//
+
+params_struct! {
+    // Capa de atmósfera de un cuerpo; se dibuja como una esfera algo mayor mezclada
+    // encima. Los valores por defecto son los de la Tierra.
+    pub struct Atmosphere {
+        // grosor relativo al radio del cuerpo (el casco se escala por 1 + thickness)
+        thickness: f32 = 0.06,
+        // dispersión Rayleigh relativa por canal: el canal que más dispersa tiñe el
+        // cielo y el que sobrevive al camino largo del atardecer tiñe el terminador
+        rayleigh: glm::Vec3 = glm::vec3(0.18, 0.41, 1.0),
+        // intensidad y asimetría (Henyey-Greenstein) de la dispersión Mie
+        mie: f32 = 0.35,
+        mie_g: f32 = 0.76,
+        // color propio de la neblina (multiplica el resultado)
+        tint: glm::Vec3 = glm::vec3(1.0, 1.0, 1.0),
+        // opacidad máxima en el borde
+        density: f32 = 0.85,
+    }
+}
+
+impl BodyShader {
+    pub fn atmosphere(&self, params: &ShaderParams) -> Option<Atmosphere> {
+        match self {
+            BodyShader::Rocky => Some(params.earth_atmosphere),
+            BodyShader::Venus => Some(params.venus_atmosphere),
+            BodyShader::Procedural(p) if p.biome == Biome::Rocky => Some(Atmosphere {
+                thickness: 0.05,
+                rayleigh: glm::vec3(0.18, 0.41, 1.0),
+                mie: 0.3,
+                mie_g: 0.76,
+                tint: glm::vec3(1.0, 1.0, 1.0),
+                density: 0.75,
+            }),
+            BodyShader::Custom(s) => s.atmosphere(),
+            _ => None,
+        }
+    }
+}
+
+// Color + alfa del casco (fase de Mie a partir de `ray_view` y `sun_view`)
+fn shade_atmosphere(
+    atm: &Atmosphere,
+    f: &Fragment,
+    sun: &SunLight,
+    ray_view: Vec3,
+    sun_view: Vec3,
+) -> Color {
+    // 1 en el centro del disco, 0 en el borde: el camino óptico crece hacia el borde
+    let mu = saturate(-glm::dot(&f.n_view, &ray_view));
+    let rim = (1.0 - mu).powf(2.5);
+    let haze = 0.12;
+
+    // la atmósfera sigue iluminada un poco más allá del terminador
+    let ndl = glm::dot(&f.n, &sun.dir);
+    let day = smoothstep(-0.35, 0.25, ndl) * sun.visibility;
+    let sunset = (-(ndl / 0.22).powi(2)).exp() * sun.visibility;
+
+    let beta = atm.rayleigh / atm.rayleigh.max().max(1e-5);
+    let sky = beta;
+    let sunset_col = glm::vec3(
+        (-beta.x * 3.0).exp(),
+        (-beta.y * 3.0).exp(),
+        (-beta.z * 3.0).exp(),
+    ) * 1.6;
+
+    // Henyey-Greenstein normalizada a 1 para g = 0; fuerte al mirar hacia el Sol
+    let g = atm.mie_g;
+    let cos_t = glm::dot(&ray_view, &sun_view);
+    let phase = (1.0 - g * g) / (1.0 + g * g - 2.0 * g * cos_t).max(1e-4).powf(1.5);
+    let mie = atm.mie * phase.min(8.0) / 8.0;
+
+    let mie_col = glm::vec3(1.0, 0.95, 0.85);
+    let mut col = lerp(sky, sunset_col, sunset * 0.85) * day + mie_col * (mie * day);
+    col = col.component_mul(&atm.tint);
+
+    let alpha = atm.density * saturate(rim + haze) * saturate(day + sunset * 0.5 + mie);
+
+    let r = (col.x.clamp(0.0, 1.0) * 255.0) as u8;
+    let g = (col.y.clamp(0.0, 1.0) * 255.0) as u8;
+    let b = (col.z.clamp(0.0, 1.0) * 255.0) as u8;
+    Color::new(r, g, b, (alpha.clamp(0.0, 1.0) * 255.0) as u8)
+}
+
+//
+// ----- NUBES (CASCO TRANSLÚCIDO) -----
+//
+
+params_struct! {
+    // Capa de nubes: casco propio que gira respecto a la superficie y le proyecta sombra
+    pub struct CloudLayer {
+        // fracción del cielo cubierta (0 = despejado, 1 = cubierto)
+        coverage: f32 = 0.45,
+        // velocidad angular del casco respecto a la superficie (rad/s, eje propio)
+        speed: f32 = 0.12,
+        // altura del casco relativa al radio del cuerpo
+        altitude: f32 = 0.025,
+        // oscurecimiento máximo de la luz directa bajo una nube densa
+        shadow_strength: f32 = 0.55,
+    }
+}
+
+impl BodyShader {
+    pub fn clouds(&self, params: &ShaderParams) -> Option<CloudLayer> {
+        match self {
+            BodyShader::Rocky => Some(params.earth_clouds),
+            BodyShader::Custom(s) => s.clouds(),
+            _ => None,
+        }
+    }
+}
+
+impl CloudLayer {
+    // Densidad (0..1) en la dirección `d` (unitaria, espacio modelo del cuerpo).
+    // fBm sobre un dominio deformado que evoluciona despacio: remolinos sin costura.
+    fn density(&self, d: Vec3, time: f32) -> f32 {
+        let (s, c) = (self.speed * time).sin_cos();
+        let d = glm::vec3(d.x * c - d.z * s, d.y, d.x * s + d.z * c);
+
+        let noise = SURFACE_NOISE.derive(9);
+        let evolve = glm::vec3(time * 0.02, time * 0.015, -time * 0.01);
+        let q = domain_warp(d * 2.2 + evolve, 0.55, |p| noise.simplex(p));
+        let n = fbm(q, &Fractal::octaves(5), |p| noise.simplex(p)) * 2.2;
+        let v = 0.5 + 0.5 * n.clamp(-1.0, 1.0);
+
+        // frentes más frecuentes en latitudes medias que en el ecuador
+        let band = 1.0 - 0.35 * (d.y * 3.0).cos().abs() * (1.0 - d.y.abs());
+        let threshold = 1.0 - self.coverage;
+        smoothstep(threshold - 0.12, threshold + 0.12, v * band + 0.15 * (1.0 - band))
+    }
+
+    // Fracción de luz directa que atraviesa la capa hasta el punto de superficie con
+    // normal `n`: se sigue el rayo hacia el Sol hasta la altura del casco
+    fn transmittance(&self, n: Vec3, sun_dir: Vec3, time: f32) -> f32 {
+        let c = glm::dot(&n, &sun_dir);
+        if c <= 0.0 {
+            return 1.0;
+        }
+        let top = 1.0 + self.altitude;
+        let t = -c + (c * c + top * top - 1.0).sqrt();
+        let q = glm::normalize(&(n + sun_dir * t));
+        1.0 - self.shadow_strength * self.density(q, time)
+    }
+}
+
+fn shade_clouds(layer: &CloudLayer, f: &Fragment, sun: &SunLight, time: f32) -> Color {
+    let density = layer.density(f.n, time);
+    let cloud = glm::vec3(0.96, 0.97, 1.0) * sun_light(f.n, sun);
+
+    let r = (cloud.x.clamp(0.0, 1.0) * 255.0) as u8;
+    let g = (cloud.y.clamp(0.0, 1.0) * 255.0) as u8;
+    let b = (cloud.z.clamp(0.0, 1.0) * 255.0) as u8;
+    Color::new(r, g, b, (density * 0.92 * 255.0) as u8)
+}
+
+//
+// ----- CASCOS TRANSLÚCIDOS -----
+//
+
+#[derive(Copy, Clone, Debug)]
+pub enum ShellLayer {
+    Clouds(CloudLayer),
+    Atmosphere(Atmosphere),
+}
+
+impl ShellLayer {
+    // Escala del casco respecto a la esfera del cuerpo
+    pub fn scale(&self) -> f32 {
+        match self {
+            ShellLayer::Clouds(c) => 1.0 + c.altitude,
+            ShellLayer::Atmosphere(a) => 1.0 + a.thickness,
+        }
+    }
+}
+
+impl BodyShader {
+    // Cascos del cuerpo de dentro hacia fuera (orden de dibujo)
+    pub fn shells(&self, params: &ShaderParams) -> Vec<ShellLayer> {
+        let mut shells = Vec::new();
+        if let Some(c) = self.clouds(params) {
+            shells.push(ShellLayer::Clouds(c));
+        }
+        if let Some(a) = self.atmosphere(params) {
+            shells.push(ShellLayer::Atmosphere(a));
+        }
+        shells
+    }
+}
+
+// `ray_view`: dirección cámara -> cuerpo en vista; `sun_view`: cuerpo -> Sol en vista
+pub fn shade_shell(
+    layer: &ShellLayer,
+    f: &Fragment,
+    sun: &SunLight,
+    ray_view: Vec3,
+    sun_view: Vec3,
+    time: f32,
+) -> Color {
+    match layer {
+        ShellLayer::Clouds(c) => shade_clouds(c, f, sun, time),
+        ShellLayer::Atmosphere(a) => shade_atmosphere(a, f, sun, ray_view, sun_view),
+    }
+}
+
+//
+// ----- PARÁMETROS -----
+//
+
+// Archivo con los parámetros editables de los shaders
+pub const SHADER_PARAMS_PATH: &str = "assets/shaders.cfg";
+
+// Parámetros de los shaders de los cuerpos fijos. Los valores por defecto dan el
+// aspecto original; `load` aplica encima lo que indique el archivo.
+#[derive(Copy, Clone, Debug)]
+pub struct ShaderParams {
+    pub star: StarParams,
+    pub star_corona: CoronaParams,
+    pub star_flare: FlareParams,
+    pub earth: EarthParams,
+    pub mercury: MercuryParams,
+    pub venus: VenusParams,
+    pub mars: MarsParams,
+    pub jupiter: JupiterParams,
+    pub moon: MoonParams,
+    pub io: IoParams,
+    pub earth_atmosphere: Atmosphere,
+    pub venus_atmosphere: Atmosphere,
+    pub earth_clouds: CloudLayer,
+    pub sky: SkyParams,
+}
+
+impl Default for ShaderParams {
+    fn default() -> Self {
+        Self {
+            star: StarParams::default(),
+            star_corona: CoronaParams::default(),
+            star_flare: FlareParams::default(),
+            earth: EarthParams::default(),
+            mercury: MercuryParams::default(),
+            venus: VenusParams::default(),
+            mars: MarsParams::default(),
+            jupiter: JupiterParams::default(),
+            moon: MoonParams::default(),
+            io: IoParams::default(),
+            earth_atmosphere: Atmosphere::default(),
+            venus_atmosphere: Atmosphere {
+                thickness: 0.09,
+                rayleigh: glm::vec3(0.45, 0.55, 0.75),
+                mie: 0.8,
+                mie_g: 0.6,
+                tint: glm::vec3(1.0, 0.85, 0.55),
+                density: 0.9,
+            },
+            earth_clouds: CloudLayer::default(),
+            sky: SkyParams::default(),
+        }
+    }
+}
+
+impl ParamSections for ShaderParams {
+    fn section_mut(&mut self, name: &str) -> Option<&mut dyn ParamSection> {
+        Some(match name {
+            "star" => &mut self.star,
+            "star.corona" => &mut self.star_corona,
+            "star.flare" => &mut self.star_flare,
+            "earth" => &mut self.earth,
+            "mercury" => &mut self.mercury,
+            "venus" => &mut self.venus,
+            "mars" => &mut self.mars,
+            "jupiter" => &mut self.jupiter,
+            "moon" => &mut self.moon,
+            "io" => &mut self.io,
+            "earth.atmosphere" => &mut self.earth_atmosphere,
+            "venus.atmosphere" => &mut self.venus_atmosphere,
+            "earth.clouds" => &mut self.earth_clouds,
+            "sky" => &mut self.sky,
+            _ => return None,
+        })
+    }
+}
+
+impl ShaderParams {
+    // Valores por defecto con el archivo aplicado encima; si el archivo no existe
+    // se quedan los valores por defecto
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let mut params = Self::default();
+        match std::fs::read_to_string(path) {
+            Ok(text) => params::apply(&mut params, &text, path)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(anyhow::anyhow!("{path}: {e}")),
+        }
+        Ok(params)
+    }
+}
+
 //
 // ----- DISPATCH -----
 //
 
+// El Sol es emisivo e ignora `sun`
 pub fn shade_body(
-    kind: BodyShader,
-    p_model: Vec3,
-    n_view: Vec3,
+    kind: &BodyShader,
+    params: &ShaderParams,
+    frag: &Fragment,
     center: Vec3,
     radius: f32,
     time: f32,
+    sun: &SunLight,
 ) -> Color {
+    // Las nubes tapan parte de la luz directa que llega a la superficie
+    let shaded;
+    let sun = match kind.clouds(params) {
+        Some(clouds) => {
+            shaded = SunLight {
+                visibility: sun.visibility * clouds.transmittance(frag.n, sun.dir, time),
+                ..*sun
+            };
+            &shaded
+        }
+        None => sun,
+    };
     match kind {
-        BodyShader::Star => shade_star(p_model, n_view, center, radius, time),
-        BodyShader::Rocky => shade_rocky_earth(p_model, n_view, center, radius, time),
-        BodyShader::GasGiant => shade_gas_giant_jupiter(p_model, n_view, center, radius, time),
-        BodyShader::Moon => shade_moon(p_model, n_view, center, radius, time),
-        BodyShader::Mercury => shade_mercury(p_model, n_view, center, radius, time),
-        BodyShader::Venus => shade_venus(p_model, n_view, center, radius, time),
-        BodyShader::Mars => shade_mars(p_model, n_view, center, radius, time),
+        BodyShader::Star => shade_star(&params.star, frag, center, radius, time),
+        BodyShader::Rocky => shade_rocky_earth(&params.earth, frag, center, radius, time, sun),
+        BodyShader::GasGiant => {
+            shade_gas_giant_jupiter(&params.jupiter, frag, center, radius, time, sun)
+        }
+        BodyShader::Moon => shade_moon(&params.moon, frag, center, radius, time, sun),
+        BodyShader::Mercury => shade_mercury(&params.mercury, frag, center, radius, time, sun),
+        BodyShader::Venus => shade_venus(&params.venus, frag, center, radius, time, sun),
+        BodyShader::Mars => shade_mars(&params.mars, frag, center, radius, time, sun),
+        BodyShader::Volcanic => shade_volcanic(&params.io, frag, center, radius, time, sun),
+        BodyShader::Procedural(p) => shade_procedural(p, frag, center, radius, time, sun),
+        BodyShader::Custom(s) => s.shade(frag, center, radius, time, sun),
     }
 }
@@ -0,0 +1,164 @@
+use nalgebra_glm as glm;
+use raylib::prelude::Color;
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::Vec3;
+use crate::mesh::Mesh;
+use crate::transform::project_vertices_perspective;
+use crate::triangle::triangle_filled;
+
+// Resolución de cada cara del cubo de sombras
+pub const SHADOW_MAP_SIZE: i32 = 512;
+
+// Sesgos en texels (a la distancia del receptor) contra el acné de sombra
+const DEPTH_BIAS_TEXELS: f32 = 1.5;
+const NORMAL_OFFSET_TEXELS: f32 = 1.5;
+
+// Caras del cubo: eje de la cara y vector "up" de su cámara
+const CUBE_FACES: [([f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, -1.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+];
+
+// Mapa de sombras omnidireccional desde el Sol en el origen: 6 framebuffers de
+// solo profundidad con cámaras de 90° rasterizados con el mismo triangle_filled
+pub struct ShadowMap {
+    size: i32,
+    near: f32,
+    far: f32,
+    axes: [glm::Vec3; 6],
+    view_proj: [glm::Mat4; 6],
+    faces: Vec<FrameBuffer>,
+}
+
+impl ShadowMap {
+    // `near` fuera del Sol (que no proyecta sombra); `far` más allá del último caster
+    pub fn new(size: i32, near: f32, far: f32) -> Self {
+        let proj = glm::perspective(1.0, std::f32::consts::FRAC_PI_2, near, far);
+        let origin = glm::vec3(0.0, 0.0, 0.0);
+        let axes = CUBE_FACES.map(|(d, _)| glm::vec3(d[0], d[1], d[2]));
+        let view_proj = CUBE_FACES.map(|(d, up)| {
+            let view = glm::look_at(
+                &origin,
+                &glm::vec3(d[0], d[1], d[2]),
+                &glm::vec3(up[0], up[1], up[2]),
+            );
+            proj * view
+        });
+        let faces = (0..6)
+            .map(|_| {
+                let mut fb = FrameBuffer::new(size, size, Color::BLACK);
+                fb.set_color_write(false);
+                fb
+            })
+            .collect();
+        Self {
+            size,
+            near,
+            far,
+            axes,
+            view_proj,
+            faces,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for fb in &mut self.faces {
+            fb.clear_depth();
+        }
+    }
+
+    // Rasteriza una malla (con su matriz de modelo) como caster
+    pub fn draw_mesh(&mut self, mesh: &Mesh, model: &glm::Mat4) {
+        let world: Vec<Vec3> = mesh
+            .vertices
+            .iter()
+            .map(|v| {
+                let p = model * glm::vec4(v.x, v.y, v.z, 1.0);
+                glm::vec3(p.x, p.y, p.z)
+            })
+            .collect();
+        let tris: Vec<[usize; 3]> = mesh.faces.iter().map(|f| f.vertex_indices).collect();
+        self.draw_triangles(&world, &tris);
+    }
+
+    // Triángulos en espacio mundo. En cada cara solo se dibujan los que quedan
+    // enteros delante del plano near (sin recorte, un vértice detrás la invertiría)
+    pub fn draw_triangles(&mut self, world: &[Vec3], tris: &[[usize; 3]]) {
+        for face in 0..6 {
+            let axis = self.axes[face];
+            let depth: Vec<f32> = world.iter().map(|v| glm::dot(v, &axis)).collect();
+            if depth.iter().all(|d| *d <= self.near) {
+                continue;
+            }
+            let screen =
+                project_vertices_perspective(world, &self.view_proj[face], self.size, self.size);
+            let fb = &mut self.faces[face];
+            for &[i0, i1, i2] in tris {
+                if depth[i0] > self.near && depth[i1] > self.near && depth[i2] > self.near {
+                    triangle_filled(fb, &screen[i0], &screen[i1], &screen[i2]);
+                }
+            }
+        }
+    }
+
+    // z NDC guardado -> distancia a lo largo del eje de la cara
+    #[inline]
+    fn linear_depth(&self, z_ndc: f32) -> f32 {
+        let (n, f) = (self.near, self.far);
+        2.0 * f * n / ((f + n) - z_ndc * (f - n))
+    }
+
+    // Cara del cubo que ve `p` (eje dominante) y distancia a lo largo de ese eje
+    fn face_for(p: &Vec3) -> (usize, f32) {
+        let (ax, ay, az) = (p.x.abs(), p.y.abs(), p.z.abs());
+        if ax >= ay && ax >= az {
+            (if p.x > 0.0 { 0 } else { 1 }, ax)
+        } else if ay >= az {
+            (if p.y > 0.0 { 2 } else { 3 }, ay)
+        } else {
+            (if p.z > 0.0 { 4 } else { 5 }, az)
+        }
+    }
+
+    // Fracción de luz solar que llega a `p_world` (0 = sombra total), con PCF 3x3.
+    // `n_world` se usa para desplazar el punto de muestreo (normal offset).
+    pub fn visibility(&self, p_world: Vec3, n_world: Vec3) -> f32 {
+        let (_, dist) = Self::face_for(&p_world);
+        if dist <= self.near {
+            return 1.0;
+        }
+        // ancho de un texel a esa distancia: la cara de 90° cubre 2 * dist
+        let texel = 2.0 * dist / self.size as f32;
+        let p = p_world + n_world * (texel * NORMAL_OFFSET_TEXELS);
+        let (face, dist) = Self::face_for(&p);
+
+        let clip = self.view_proj[face] * glm::vec4(p.x, p.y, p.z, 1.0);
+        if clip.w <= 1e-6 {
+            return 1.0;
+        }
+        let size = self.size as f32;
+        let sx = ((clip.x / clip.w) * 0.5 + 0.5) * size;
+        let sy = (1.0 - ((clip.y / clip.w) * 0.5 + 0.5)) * size;
+        let (cx, cy) = (sx.floor() as i32, sy.floor() as i32);
+
+        let bias = texel * DEPTH_BIAS_TEXELS;
+        let fb = &self.faces[face];
+        let mut lit = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let x = (cx + dx).clamp(0, self.size - 1);
+                let y = (cy + dy).clamp(0, self.size - 1);
+                let stored = fb.depth_at(x, y);
+                if !stored.is_finite() || dist - bias <= self.linear_depth(stored) {
+                    lit += 1;
+                }
+            }
+        }
+        lit as f32 / 9.0
+    }
+}
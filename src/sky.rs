@@ -0,0 +1,245 @@
+use nalgebra_glm as glm;
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::Vec3;
+use crate::noise::{Fractal, Noise, domain_warp, fbm, ridged};
+use crate::params::params_struct;
+use crate::shaders::smoothstep;
+use crate::starfield::equatorial_to_world;
+
+// Cielo de fondo: Vía Láctea (banda, bulbo y polvo) y nebulosas, horneado una vez
+// a un cubemap y muestreado por dirección de vista donde no hay geometría.
+
+// Texels por lado de cada cara del cubemap; el cielo es de baja frecuencia y se
+// muestrea con filtro bilineal
+const FACE_SIZE: usize = 256;
+// Píxeles entre muestras del cubemap al dibujar (se interpola entre ellas)
+const SAMPLE_STRIDE: usize = 4;
+
+// Polo norte galáctico y centro galáctico (J2000: ascensión recta en horas,
+// declinación en grados), para que la banda coincida con el catálogo de estrellas
+const GALACTIC_NORTH: (f32, f32) = (12.857, 27.128);
+const GALACTIC_CENTER: (f32, f32) = (17.760, -28.936);
+
+params_struct! {
+    // Cielo de fondo. `intensity` se aplica al dibujar; el resto cambia el horneado.
+    #[derive(PartialEq)]
+    pub struct SkyParams {
+        // brillo del cielo sobre el negro (bajo para no competir con los cuerpos)
+        intensity: f32 = 0.35,
+        seed: f32 = 7.0,
+        band_color: glm::Vec3 = glm::vec3(0.55, 0.62, 0.80),
+        bulge_color: glm::Vec3 = glm::vec3(1.00, 0.82, 0.60),
+        // semiancho de la banda galáctica (rad), mayor hacia el centro
+        band_width: f32 = 0.20,
+        // cuánto oscurecen las franjas de polvo (0..1)
+        dust: f32 = 0.85,
+        // nebulosas de emisión (rojizas) y de reflexión (azuladas)
+        emission_color: glm::Vec3 = glm::vec3(0.90, 0.28, 0.38),
+        reflection_color: glm::Vec3 = glm::vec3(0.30, 0.50, 0.95),
+        nebula_amount: f32 = 0.5,
+    }
+}
+
+// Seis caras (+x, -x, +y, -y, +z, -z) de FACE_SIZE² colores lineales
+pub struct SkyBox {
+    faces: Vec<Vec<Vec3>>,
+    // parámetros del horneado (ver bake_key)
+    baked: SkyParams,
+}
+
+// Lo que cambia el horneado: todo menos `intensity`
+fn bake_key(p: &SkyParams) -> SkyParams {
+    SkyParams {
+        intensity: 0.0,
+        ..*p
+    }
+}
+
+impl SkyBox {
+    pub fn bake(p: &SkyParams) -> Self {
+        let galaxy = Galaxy::new();
+        let faces = (0..6)
+            .map(|face| {
+                (0..FACE_SIZE * FACE_SIZE)
+                    .map(|i| {
+                        let texel = |k: usize| (k as f32 + 0.5) / FACE_SIZE as f32 * 2.0 - 1.0;
+                        let dir = face_dir(face, texel(i % FACE_SIZE), texel(i / FACE_SIZE));
+                        // sin valores diminutos: los subnormales hacen muy lento el filtrado
+                        let color = galaxy.color(glm::normalize(&dir), p);
+                        color.map(|c| if c < 1e-4 { 0.0 } else { c })
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            faces,
+            baked: bake_key(p),
+        }
+    }
+
+    // Hornear es caro (~1 s): al recargar los parámetros solo se rehace si cambió
+    // algo más que `intensity`
+    pub fn is_baked_with(&self, p: &SkyParams) -> bool {
+        self.baked == bake_key(p)
+    }
+
+    // Color (lineal, sin intensidad) en la dirección `dir` (no hace falta normalizarla)
+    #[inline]
+    pub fn sample(&self, dir: Vec3) -> Vec3 {
+        let (face, u, v) = cube_coords(dir);
+        let texels = &self.faces[face];
+        let n = FACE_SIZE as f32;
+        // centros de texel en k + 0.5; se recorta al borde de la cara
+        let x = ((u * 0.5 + 0.5) * n - 0.5).clamp(0.0, n - 1.0);
+        let y = ((v * 0.5 + 0.5) * n - 0.5).clamp(0.0, n - 1.0);
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = ((x0 + 1).min(FACE_SIZE - 1), (y0 + 1).min(FACE_SIZE - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+        let at = |x: usize, y: usize| texels[y * FACE_SIZE + x];
+        let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+        let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    // Rellena los píxeles sin geometría (profundidad infinita) con el cielo visto
+    // con la rotación de la cámara. Va después de la geometría opaca y antes de las
+    // estrellas; no escribe profundidad. Se muestrea cada SAMPLE_STRIDE píxeles de
+    // la fila y se interpola entre medias.
+    pub fn draw(&self, fb: &mut FrameBuffer, view: &glm::Mat4, proj: &glm::Mat4, intensity: f32) {
+        if intensity <= 0.0 {
+            return;
+        }
+        let rotation = glm::mat3_to_mat4(&glm::mat4_to_mat3(view));
+        let inv_view_proj = glm::inverse(&(proj * rotation));
+        let (w, h) = (fb.width, fb.height);
+        // rayo por píxel: punto del plano lejano, lineal en x dentro de la fila
+        let step = inv_view_proj.column(0) * (2.0 / w as f32);
+        let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0) as u8;
+        for y in 0..h {
+            let ndc_y = 1.0 - (y as f32 + 0.5) / h as f32 * 2.0;
+            let first = inv_view_proj * glm::vec4(-1.0 + 1.0 / w as f32, ndc_y, 1.0, 1.0);
+            let sample_at = |x: usize| {
+                let ray = first + step * x as f32;
+                self.sample(glm::vec3(ray.x, ray.y, ray.z) / ray.w) * intensity
+            };
+
+            // muestra al final del tramo anterior (se reutiliza si son contiguos)
+            let mut previous: Option<(usize, Vec3)> = None;
+            let (row_color, row_z) = fb.row_mut(y);
+            let spans = row_color
+                .chunks_mut(SAMPLE_STRIDE)
+                .zip(row_z.chunks(SAMPLE_STRIDE));
+            for (i, (colors, depths)) in spans.enumerate() {
+                if depths.iter().all(|&z| z != f32::INFINITY) {
+                    continue;
+                }
+                let x0 = i * SAMPLE_STRIDE;
+                let left = match previous {
+                    Some((x, rgb)) if x == x0 => rgb,
+                    _ => sample_at(x0),
+                };
+                let right = sample_at(x0 + SAMPLE_STRIDE);
+                previous = Some((x0 + SAMPLE_STRIDE, right));
+
+                for (k, (c, &z)) in colors.iter_mut().zip(depths).enumerate() {
+                    if z == f32::INFINITY {
+                        let t = k as f32 / SAMPLE_STRIDE as f32;
+                        let rgb = left * (1.0 - t) + right * t;
+                        *c = [channel(rgb.x), channel(rgb.y), channel(rgb.z), 255];
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Dirección (sin normalizar) del punto (u, v) ∈ [-1, 1]² de una cara
+fn face_dir(face: usize, u: f32, v: f32) -> Vec3 {
+    match face {
+        0 => glm::vec3(1.0, -v, -u),
+        1 => glm::vec3(-1.0, -v, u),
+        2 => glm::vec3(u, 1.0, v),
+        3 => glm::vec3(u, -1.0, -v),
+        4 => glm::vec3(u, -v, 1.0),
+        _ => glm::vec3(-u, -v, -1.0),
+    }
+}
+
+// Inversa de face_dir: cara del eje dominante y (u, v) en ella
+#[inline]
+fn cube_coords(d: Vec3) -> (usize, f32, f32) {
+    let (ax, ay, az) = (d.x.abs(), d.y.abs(), d.z.abs());
+    if ax >= ay && ax >= az {
+        if d.x > 0.0 {
+            (0, -d.z / ax, -d.y / ax)
+        } else {
+            (1, d.z / ax, -d.y / ax)
+        }
+    } else if ay >= az {
+        if d.y > 0.0 {
+            (2, d.x / ay, d.z / ay)
+        } else {
+            (3, d.x / ay, -d.z / ay)
+        }
+    } else if d.z > 0.0 {
+        (4, d.x / az, -d.y / az)
+    } else {
+        (5, -d.x / az, -d.y / az)
+    }
+}
+
+// Marco galáctico en coordenadas de mundo
+struct Galaxy {
+    north: Vec3,
+    center: Vec3,
+    east: Vec3,
+}
+
+impl Galaxy {
+    fn new() -> Self {
+        let north = equatorial_to_world(GALACTIC_NORTH.0, GALACTIC_NORTH.1);
+        let center = equatorial_to_world(GALACTIC_CENTER.0, GALACTIC_CENTER.1);
+        // se ortogonaliza (los valores tabulados están redondeados)
+        let center = glm::normalize(&(center - north * glm::dot(&center, &north)));
+        Self {
+            north,
+            center,
+            east: glm::cross(&north, &center),
+        }
+    }
+
+    // Color del cielo en la dirección unitaria `d`
+    fn color(&self, d: Vec3, p: &SkyParams) -> Vec3 {
+        let noise = Noise::new(p.seed as u32);
+        // latitud y longitud galácticas (longitud 0 = centro galáctico)
+        let b = glm::dot(&d, &self.north).clamp(-1.0, 1.0).asin();
+        let l = glm::dot(&d, &self.east).atan2(glm::dot(&d, &self.center));
+
+        // Banda: perfil gaussiano en latitud, más ancha y brillante hacia el centro
+        let toward_center = (-(l / 1.2).powi(2)).exp();
+        let width = p.band_width * (1.0 + 0.8 * toward_center);
+        let band = (-(b / width).powi(2)).exp();
+        let clumps = fbm(d * 5.0, &Fractal::octaves(5), |q| noise.simplex(q)) * 0.5 + 0.5;
+        let bulge = (-(l * l + (b * 2.5).powi(2)) / 0.15).exp();
+        let mut color = p.band_color * (band * (0.3 + 0.7 * clumps) * (0.6 + 0.4 * toward_center))
+            + p.bulge_color * (bulge * (0.6 + 0.4 * clumps));
+
+        // Polvo: filamentos oscuros a lo largo del plano medio de la banda
+        let dust = noise.derive(1);
+        let q = domain_warp(d * 3.0, 0.35, |q| dust.simplex(q));
+        let lanes = ridged(q, &Fractal::octaves(5), |q| dust.simplex(q));
+        let mid_plane = (-(b / (width * 0.45)).powi(2)).exp();
+        color *= 1.0 - p.dust * mid_plane * smoothstep(0.3, 0.75, lanes);
+
+        // Nebulosas: nubes de fBm umbralizado, más frecuentes cerca del plano
+        let nebula = noise.derive(2);
+        let q = domain_warp(d * 2.0, 0.3, |q| nebula.simplex(q));
+        let cloud = fbm(q, &Fractal::octaves(6), |q| nebula.simplex(q));
+        let near_plane = 0.15 + 0.85 * (-(b / (width * 2.5)).powi(2)).exp();
+        let amount = smoothstep(0.2, 0.6, cloud) * near_plane * p.nebula_amount;
+        let tint = smoothstep(-0.3, 0.3, nebula.derive(1).simplex(d * 1.5));
+        let tint = p.emission_color * (1.0 - tint) + p.reflection_color * tint;
+        color + tint * amount
+    }
+}
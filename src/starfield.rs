@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use nalgebra_glm as glm;
+use raylib::prelude::Color;
+
+use crate::framebuffer::FrameBuffer;
+use crate::geom::{Vec2, Vec3};
+use crate::line::line_depth_thick;
+use crate::noise::Noise;
+use crate::params::line_error;
+
+pub const STAR_CATALOG_PATH: &str = "assets/stars.txt";
+// Figuras de constelaciones: archivo junto al catálogo
+const CONSTELLATIONS_FILE: &str = "constellations.txt";
+
+// Profundidad de las estrellas: más lejos que todo, incluso que las órbitas
+// (que se dibujan a z fijo muy lejano), así cualquier cosa las tapa
+const STAR_DEPTH: f32 = f32::MAX;
+
+// Oblicuidad de la eclíptica (rad): el plano de las órbitas (xz) es la eclíptica
+const OBLIQUITY: f32 = 0.409_1;
+// Magnitud que ya se ve con brillo pleno; las más débiles se atenúan con su flujo
+const MAG_FULL: f32 = 2.0;
+
+// Cielo procedural si no hay catálogo: cantidad, semilla y magnitud límite
+const PROCEDURAL_COUNT: usize = 2500;
+const PROCEDURAL_SEED: u32 = 1977;
+const PROCEDURAL_MAG_LIMIT: f32 = 6.0;
+
+const CONSTELLATION_COLOR: Color = Color::new(80, 120, 190, 70);
+// Extremo de una figura demasiado cerca del plano de la cámara: se descarta
+const MIN_LINE_W: f32 = 0.05;
+
+struct Star {
+    // dirección en la esfera celeste (espacio mundo)
+    dir: Vec3,
+    color: Color,
+    // 1 = un píxel, 2 = cruz, 3 = disco pequeño
+    size: u8,
+}
+
+impl Star {
+    // Brillo por magnitud (flujo 10^(-0.4·m), comprimido) y color de cuerpo negro
+    // por índice B-V
+    fn new(dir: Vec3, mag: f32, bv: f32) -> Self {
+        let brightness = 10f32.powf(-0.4 * (mag - MAG_FULL)).sqrt().min(1.0);
+        let rgb = blackbody(bv_temperature(bv));
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0) as u8;
+        let size = match mag {
+            m if m < 1.0 => 3,
+            m if m < 2.5 => 2,
+            _ => 1,
+        };
+        Self {
+            dir,
+            color: Color::new(
+                channel(rgb.x),
+                channel(rgb.y),
+                channel(rgb.z),
+                channel(brightness),
+            ),
+            size,
+        }
+    }
+}
+
+// Estrellas fijas en la esfera celeste, en el infinito: solo les afecta la
+// rotación de la cámara, no su posición. Las figuras de constelaciones son
+// segmentos entre direcciones de estrellas.
+pub struct Starfield {
+    stars: Vec<Star>,
+    lines: Vec<(Vec3, Vec3)>,
+}
+
+impl Starfield {
+    // Cielo aleatorio con la distribución de magnitudes del cielo real (hay ~3
+    // veces más estrellas por cada magnitud más débil)
+    pub fn generate(count: usize, seed: u32) -> Self {
+        let noise = Noise::new(seed);
+        let stars = (0..count as u32)
+            .map(|i| {
+                let rand = |k: u32| noise.random(i * 4 + k);
+                // uniforme sobre la esfera
+                let z = rand(0) * 2.0 - 1.0;
+                let phi = rand(1) * std::f32::consts::TAU;
+                let ring = (1.0 - z * z).sqrt();
+                let dir = glm::vec3(ring * phi.cos(), z, ring * phi.sin());
+
+                let mag = PROCEDURAL_MAG_LIMIT + 2.0 * (1.0 - rand(2)).log10();
+                let bv = -0.3 + 2.0 * rand(3);
+                Star::new(dir, mag.max(-1.5), bv)
+            })
+            .collect();
+        Self {
+            stars,
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn procedural() -> Self {
+        Self::generate(PROCEDURAL_COUNT, PROCEDURAL_SEED)
+    }
+
+    // Catálogo del archivo (o cielo procedural si no existe) y, si está junto a
+    // él, el archivo de constelaciones
+    pub fn load(path: &str) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::procedural()),
+            Err(e) => return Err(anyhow!("{path}: {e}")),
+        };
+        let (stars, names) = parse_catalog(&text, path)?;
+
+        let lines_origin = Self::constellations_path(path);
+        let lines = match std::fs::read_to_string(&lines_origin) {
+            Ok(text) => parse_constellations(&text, &lines_origin, &stars, &names)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(anyhow!("{lines_origin}: {e}")),
+        };
+        Ok(Self { stars, lines })
+    }
+
+    // Archivo de constelaciones que acompaña al catálogo `catalog_path`
+    pub fn constellations_path(catalog_path: &str) -> String {
+        let path = Path::new(catalog_path).with_file_name(CONSTELLATIONS_FILE);
+        path.display().to_string()
+    }
+
+    // Proyecta cada dirección como punto en el infinito (w = 0) y la mezcla con el
+    // fondo según su brillo, con prueba de profundidad y sin escribirla. Se dibuja
+    // después de la geometría opaca y antes de los translúcidos.
+    pub fn draw(
+        &self,
+        fb: &mut FrameBuffer,
+        view: &glm::Mat4,
+        proj: &glm::Mat4,
+        constellations: bool,
+    ) {
+        let (w, h) = (fb.width as f32, fb.height as f32);
+        let rotation = glm::mat3_to_mat4(&glm::mat4_to_mat3(view));
+        let view_proj = proj * rotation;
+        // (punto en pantalla, w del clip)
+        let project = |dir: Vec3| {
+            let clip = view_proj * glm::vec4(dir.x, dir.y, dir.z, 0.0);
+            let ndc = glm::vec2(clip.x, clip.y) / clip.w;
+            (
+                glm::vec2((ndc.x * 0.5 + 0.5) * w, (1.0 - (ndc.y * 0.5 + 0.5)) * h),
+                clip.w,
+            )
+        };
+
+        fb.set_blend(true);
+        // Figuras primero: las estrellas quedan encima de sus líneas. Una figura
+        // (arco de círculo máximo) se proyecta como una recta.
+        if constellations {
+            fb.set_color(CONSTELLATION_COLOR);
+            for &(a, b) in &self.lines {
+                let ((pa, wa), (pb, wb)) = (project(a), project(b));
+                if wa < MIN_LINE_W || wb < MIN_LINE_W {
+                    continue;
+                }
+                if let Some((pa, pb)) = clip_segment(pa, pb, w, h) {
+                    let za = glm::vec3(pa.x, pa.y, STAR_DEPTH);
+                    let zb = glm::vec3(pb.x, pb.y, STAR_DEPTH);
+                    line_depth_thick(fb, &za, &zb, 0);
+                }
+            }
+        }
+
+        for star in &self.stars {
+            let (p, clip_w) = project(star.dir);
+            if clip_w <= 1e-6 || p.x < -2.0 || p.y < -2.0 || p.x > w + 2.0 || p.y > h + 2.0 {
+                continue;
+            }
+            let (x, y) = (p.x.floor() as i32, p.y.floor() as i32);
+
+            fb.set_color(star.color);
+            fb.set_pixel_z(x, y, STAR_DEPTH);
+            if star.size >= 2 {
+                // brazos de la cruz a media intensidad
+                let mut arm = star.color;
+                arm.a /= 2;
+                fb.set_color(arm);
+                for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    fb.set_pixel_z(x + dx, y + dy, STAR_DEPTH);
+                }
+            }
+            if star.size >= 3 {
+                let mut corner = star.color;
+                corner.a /= 5;
+                fb.set_color(corner);
+                for (dx, dy) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+                    fb.set_pixel_z(x + dx, y + dy, STAR_DEPTH);
+                }
+            }
+        }
+        fb.set_blend(false);
+    }
+}
+
+//
+// ----- ARCHIVOS -----
+//
+
+// Líneas `nombre ar(h) dec(°) magnitud b-v`; devuelve las estrellas y el índice
+// de cada nombre (salvo `-`)
+fn parse_catalog(text: &str, origin: &str) -> Result<(Vec<Star>, HashMap<String, usize>)> {
+    let mut stars = Vec::new();
+    let mut names = HashMap::new();
+    for (lineno, line) in data_lines(text) {
+        let err = |msg: String| line_error(origin, lineno, msg);
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [name, values @ ..] = fields.as_slice() else {
+            unreachable!("data_lines no devuelve líneas vacías");
+        };
+        let values: Vec<f32> = values
+            .iter()
+            .map(|v| v.parse::<f32>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| err(format!("números inválidos: {line}")))?;
+        let [ra, dec, mag, bv] = values[..] else {
+            return Err(err(format!(
+                "se esperaba `nombre ar dec magnitud b-v`: {line}"
+            )));
+        };
+
+        if *name != "-" && names.insert(name.to_string(), stars.len()).is_some() {
+            return Err(err(format!("estrella repetida '{name}'")));
+        }
+        stars.push(Star::new(equatorial_to_world(ra, dec), mag, bv));
+    }
+    if stars.is_empty() {
+        return Err(anyhow!("{origin}: catálogo vacío"));
+    }
+    Ok((stars, names))
+}
+
+// Líneas `Constelación: estrella estrella ...`; cada par consecutivo es un segmento
+fn parse_constellations(
+    text: &str,
+    origin: &str,
+    stars: &[Star],
+    names: &HashMap<String, usize>,
+) -> Result<Vec<(Vec3, Vec3)>> {
+    let mut lines = Vec::new();
+    for (lineno, line) in data_lines(text) {
+        let err = |msg: String| line_error(origin, lineno, msg);
+        let (_, chain) = line
+            .split_once(':')
+            .ok_or_else(|| err(format!("se esperaba `Constelación: estrellas`: {line}")))?;
+        let dirs: Vec<Vec3> = chain
+            .split_whitespace()
+            .map(|name| {
+                names
+                    .get(name)
+                    .map(|&i| stars[i].dir)
+                    .ok_or_else(|| err(format!("estrella desconocida '{name}'")))
+            })
+            .collect::<Result<_>>()?;
+        if dirs.len() < 2 {
+            return Err(err(format!(
+                "la figura necesita al menos dos estrellas: {line}"
+            )));
+        }
+        lines.extend(dirs.windows(2).map(|pair| (pair[0], pair[1])));
+    }
+    Ok(lines)
+}
+
+// Líneas con contenido (sin comentarios `#`) y su número desde 1
+fn data_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, raw)| (i + 1, raw.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+//
+// ----- ASTRONOMÍA -----
+//
+
+// Ascensión recta (horas) y declinación (grados) a dirección de mundo: se pasa a
+// coordenadas eclípticas y el polo norte de la eclíptica queda en +y
+pub(crate) fn equatorial_to_world(ra_hours: f32, dec_deg: f32) -> Vec3 {
+    let (ra, dec) = ((ra_hours * 15.0).to_radians(), dec_deg.to_radians());
+    // ecuatoriales: x al equinoccio vernal, z al polo norte celeste
+    let eq = glm::vec3(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+    // giro de la oblicuidad alrededor de la recta de los equinoccios
+    let (s, c) = OBLIQUITY.sin_cos();
+    let ecliptic = glm::vec3(eq.x, eq.y * c + eq.z * s, eq.z * c - eq.y * s);
+    glm::vec3(ecliptic.x, ecliptic.z, -ecliptic.y)
+}
+
+// Temperatura efectiva (K) a partir del índice de color B-V (Ballesteros, 2012)
+fn bv_temperature(bv: f32) -> f32 {
+    let bv = bv.clamp(-0.4, 2.0);
+    4600.0 * (1.0 / (0.92 * bv + 1.7) + 1.0 / (0.92 * bv + 0.62))
+}
+
+// Color aproximado de un cuerpo negro a temperatura `kelvin`, normalizado para
+// que el canal mayor valga 1 (el brillo lo da la magnitud)
+fn blackbody(kelvin: f32) -> Vec3 {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let r = if t <= 66.0 {
+        1.0
+    } else {
+        1.292_936 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let g = if t <= 66.0 {
+        0.390_081_6 * t.ln() - 0.631_841_4
+    } else {
+        1.129_890_9 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let b = if t >= 66.0 {
+        1.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        0.543_206_8 * (t - 10.0).ln() - 1.196_254_1
+    };
+    let rgb = glm::vec3(r, g, b).map(|c| c.clamp(0.0, 1.0));
+    rgb / rgb.max().max(1e-4)
+}
+
+// Recorta el segmento al rectángulo de la imagen (Liang-Barsky); None si queda fuera
+fn clip_segment(a: Vec2, b: Vec2, w: f32, h: f32) -> Option<(Vec2, Vec2)> {
+    let d = b - a;
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for (p, q) in [
+        (-d.x, a.x),
+        (d.x, w - 1.0 - a.x),
+        (-d.y, a.y),
+        (d.y, h - 1.0 - a.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = q / p;
+        if p < 0.0 {
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+        if t0 > t1 {
+            return None;
+        }
+    }
+    Some((a + d * t0, a + d * t1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = "\
+# nombre ar dec magnitud b-v
+Vernal   0.0   0.0 -0.5 -0.3   # azul y muy brillante
+Polar    0.0  90.0  2.0  0.6
+-       12.0   0.0  4.5  1.8
+-        6.0 -45.0  6.0  1.8
+";
+
+    fn error(text: &str) -> String {
+        match parse_catalog(text, "stars.txt") {
+            Ok(_) => panic!("se aceptó:\n{text}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn catalog_columns() {
+        let (stars, names) = parse_catalog(CATALOG, "stars.txt").unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(stars.len(), 4);
+        // las estrellas sin nombre no entran en el índice (y pueden repetirse)
+        assert_eq!(names.len(), 2);
+        let (vernal, polar) = (&stars[names["Vernal"]], &stars[names["Polar"]]);
+        let (faint, faintest) = (&stars[2], &stars[3]);
+
+        // ar/dec: el equinoccio vernal en +x, ar 12 h en -x y el polo norte celeste
+        // a la oblicuidad del polo de la eclíptica (+y)
+        let near = |a: Vec3, b: Vec3| glm::distance(&a, &b) < 1e-5;
+        assert!(near(vernal.dir, glm::vec3(1.0, 0.0, 0.0)));
+        assert!(near(faint.dir, glm::vec3(-1.0, 0.0, 0.0)));
+        let pole = glm::vec3(0.0, OBLIQUITY.cos(), -OBLIQUITY.sin());
+        assert!(near(polar.dir, pole));
+
+        // magnitud: tamaño y brillo (pleno hasta MAG_FULL)
+        assert_eq!((vernal.size, polar.size, faint.size), (3, 2, 1));
+        assert_eq!((vernal.color.a, polar.color.a), (255, 255));
+        assert!(faintest.color.a < faint.color.a && faint.color.a < 255);
+
+        // b-v: azul la caliente, roja la fría
+        assert!(vernal.color.b > vernal.color.r);
+        assert!(faint.color.r > faint.color.b);
+    }
+
+    #[test]
+    fn catalog_errors() {
+        assert_eq!(
+            error("Sol 1.0 2.0 3.0 x\n"),
+            "stars.txt:1: números inválidos: Sol 1.0 2.0 3.0 x"
+        );
+        assert_eq!(
+            error("# sin b-v\nSol 1.0 2.0 3.0\n"),
+            "stars.txt:2: se esperaba `nombre ar dec magnitud b-v`: Sol 1.0 2.0 3.0"
+        );
+        assert_eq!(
+            error("Sol 1 2 3 0.5 7\n"),
+            "stars.txt:1: se esperaba `nombre ar dec magnitud b-v`: Sol 1 2 3 0.5 7"
+        );
+        assert_eq!(
+            error("Sol 1 2 3 0\n\nSol 2 3 4 0\n"),
+            "stars.txt:3: estrella repetida 'Sol'"
+        );
+        assert_eq!(error("# vacío\n\n"), "stars.txt: catálogo vacío");
+    }
+
+    #[test]
+    fn shipped_catalog_loads() {
+        let sky = Starfield::load(STAR_CATALOG_PATH).unwrap_or_else(|e| panic!("{e}"));
+        assert!(!sky.stars.is_empty());
+        assert!(!sky.lines.is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use raylib::prelude::Color;
+
 use crate::framebuffer::FrameBuffer;
 use crate::geom::Vec3;
 use crate::line::line;
@@ -9,7 +11,52 @@ pub fn triangle(fb: &mut FrameBuffer, a: &Vec3, b: &Vec3, c: &Vec3) {
     line(fb, c, a);
 }
 
+// Baricéntricas de una fila: w_k(px) = a_k * px + b_k, con px = x + 0.5
+#[derive(Clone, Copy)]
+struct RowBary {
+    a: [f32; 3],
+    b: [f32; 3],
+}
+
+impl RowBary {
+    #[inline]
+    fn at(&self, x: i32) -> [f32; 3] {
+        let px = x as f32 + 0.5;
+        [
+            self.a[0] * px + self.b[0],
+            self.a[1] * px + self.b[1],
+            self.a[2] * px + self.b[2],
+        ]
+    }
+}
+
 pub fn triangle_filled(fb: &mut FrameBuffer, a: &Vec3, b: &Vec3, c: &Vec3) {
+    rasterize(fb, a, b, c, |fb, y, x0, x1, z0, dz, _| {
+        fb.fill_span_z(y, x0, x1, z0, dz)
+    });
+}
+
+// Relleno con color por píxel: `shade` recibe las baricéntricas (a, b, c) del
+// centro del píxel y solo se llama si el píxel pasa la prueba de profundidad
+pub fn triangle_filled_shaded(
+    fb: &mut FrameBuffer,
+    a: &Vec3,
+    b: &Vec3,
+    c: &Vec3,
+    mut shade: impl FnMut([f32; 3]) -> Color,
+) {
+    rasterize(fb, a, b, c, |fb, y, x0, x1, z0, dz, row| {
+        fb.fill_span_z_shaded(y, x0, x1, z0, dz, |x| shade(row.at(x)))
+    });
+}
+
+// Recorre los tramos [x0, x1] de cada fila cubierta por el triángulo
+fn rasterize<F>(fb: &mut FrameBuffer, a: &Vec3, b: &Vec3, c: &Vec3, mut span: F)
+where
+    F: FnMut(&mut FrameBuffer, i32, i32, i32, f32, f32, &RowBary),
+{
+    fb.stats.triangles_submitted += 1;
+
     // evita NaN/Inf que rompen el bbox
     if !a.x.is_finite()
         || !a.y.is_finite()
@@ -21,6 +68,7 @@ pub fn triangle_filled(fb: &mut FrameBuffer, a: &Vec3, b: &Vec3, c: &Vec3) {
         || !c.y.is_finite()
         || !c.z.is_finite()
     {
+        fb.stats.triangles_culled += 1;
         return;
     }
 
@@ -30,27 +78,61 @@ pub fn triangle_filled(fb: &mut FrameBuffer, a: &Vec3, b: &Vec3, c: &Vec3) {
     let max_y = a.y.max(b.y).max(c.y).ceil().min((fb.height - 1) as f32) as i32;
 
     let den = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
-    if den.abs() < 1e-6 {
+    // degenerado o completamente fuera de pantalla
+    if den.abs() < 1e-6 || min_x > max_x || min_y > max_y {
+        fb.stats.triangles_culled += 1;
         return;
     }
+    fb.stats.triangles_rasterized += 1;
 
     let eps = 1e-6; // tolerancia para evitar agujeros por redondeo
 
+    // Las baricéntricas son lineales en x dentro de cada fila: w = A * px + B.
+    // En vez de probar píxel por píxel, se resuelve el tramo [x0, x1] que cae
+    // dentro del triángulo y se escribe de una vez.
+    let a0 = (b.y - c.y) / den;
+    let a1 = (c.y - a.y) / den;
+    let a2 = -a0 - a1;
+    let dz = a0 * a.z + a1 * b.z + a2 * c.z;
+
     for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            let px = x as f32 + 0.5;
-            let py = y as f32 + 0.5;
-
-            // barycentric (orientación-invariante)
-            let w0 = ((b.y - c.y) * (px - c.x) + (c.x - b.x) * (py - c.y)) / den;
-            let w1 = ((c.y - a.y) * (px - c.x) + (a.x - c.x) * (py - c.y)) / den;
-            let w2 = 1.0 - w0 - w1;
-
-            if w0 >= -eps && w1 >= -eps && w2 >= -eps {
-                // interpola z en NDC
-                let z = w0 * a.z + w1 * b.z + w2 * c.z;
-                fb.set_pixel_z(x, y, z);
+        let py = y as f32 + 0.5;
+
+        // barycentric (orientación-invariante) evaluadas en px = 0
+        let b0 = ((c.x - b.x) * (py - c.y) - (b.y - c.y) * c.x) / den;
+        let b1 = ((a.x - c.x) * (py - c.y) - (c.y - a.y) * c.x) / den;
+        let b2 = 1.0 - b0 - b1;
+
+        let mut lo = min_x as f32 + 0.5;
+        let mut hi = max_x as f32 + 0.5;
+        let mut empty = false;
+        for (ak, bk) in [(a0, b0), (a1, b1), (a2, b2)] {
+            // ak * px + bk >= -eps
+            if ak > 0.0 {
+                lo = lo.max((-eps - bk) / ak);
+            } else if ak < 0.0 {
+                hi = hi.min((-eps - bk) / ak);
+            } else if bk < -eps {
+                empty = true;
             }
         }
+        if empty {
+            continue;
+        }
+
+        let x0 = ((lo - 0.5).ceil() as i32).max(min_x);
+        let x1 = ((hi - 0.5).floor() as i32).min(max_x);
+        if x0 > x1 {
+            continue;
+        }
+
+        // interpola z en NDC
+        let px0 = x0 as f32 + 0.5;
+        let z0 = (a0 * px0 + b0) * a.z + (a1 * px0 + b1) * b.z + (a2 * px0 + b2) * c.z;
+        let row = RowBary {
+            a: [a0, a1, a2],
+            b: [b0, b1, b2],
+        };
+        span(fb, y, x0, x1, z0, dz, &row);
     }
 }
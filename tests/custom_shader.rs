@@ -0,0 +1,81 @@
+// Extensión desde fuera de la biblioteca: un SurfaceShader propio registrado con
+// ShaderRegistry::register se elige por nombre en una escena, se dibuja por el
+// mismo dispatch que los del sistema y se libera con el registro y la escena.
+
+use std::sync::Arc;
+
+use nalgebra_glm as glm;
+use raylib::prelude::Color;
+
+use spacetravel::geom::Vec3;
+use spacetravel::registry::{ShaderRegistry, SurfaceShader};
+use spacetravel::scene::Scene;
+use spacetravel::shaders::{BodyShader, Fragment, ShaderParams, SunLight, shade_body};
+
+const BASALT: Color = Color::new(40, 38, 36, 255);
+
+// Superficie lisa de un solo color, más clara en el lado de día
+struct Basalt;
+
+impl SurfaceShader for Basalt {
+    fn label(&self) -> &'static str {
+        "Basalto"
+    }
+
+    fn shade(&self, frag: &Fragment, _: Vec3, _: f32, _: f32, sun: &SunLight) -> Color {
+        if glm::dot(&frag.n, &sun.dir) > 0.0 {
+            BASALT
+        } else {
+            Color::BLACK
+        }
+    }
+}
+
+#[test]
+fn registered_shader_resolves_from_scene_kind() {
+    let mut registry = ShaderRegistry::with_builtins();
+    registry.register("basalt", Basalt);
+    let scene = Scene::parse(
+        "[planet]\nkind = basalt\norbit = 2.0\n",
+        "prueba.cfg",
+        &registry,
+        1,
+    )
+    .expect("la escena debería aceptar el shader registrado");
+
+    let kind = &scene.planets[0].kind;
+    assert_eq!(kind.label(), "Basalto");
+    let BodyShader::Custom(shader) = kind else {
+        panic!("`kind = basalt` debería resolverse al shader registrado");
+    };
+
+    let up = glm::vec3(0.0, 1.0, 0.0);
+    let frag = Fragment {
+        p: up,
+        n: up,
+        n_view: up,
+    };
+    let sun = SunLight {
+        dir: up,
+        visibility: 1.0,
+    };
+    let color = shade_body(
+        kind,
+        &ShaderParams::default(),
+        &frag,
+        Vec3::zeros(),
+        1.0,
+        0.0,
+        &sun,
+    );
+    assert_eq!((color.r, color.g, color.b), (BASALT.r, BASALT.g, BASALT.b));
+
+    // Sin escena ni registro no queda ninguna referencia al shader
+    let shader = Arc::downgrade(shader);
+    drop(scene);
+    drop(registry);
+    assert!(
+        shader.upgrade().is_none(),
+        "el shader registrado no se liberó"
+    );
+}
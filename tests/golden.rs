@@ -0,0 +1,273 @@
+// Pruebas de regresión visual de los shaders de cuerpos. Cada shader se dibuja sin
+// ventana sobre la esfera base, a tiempos y poses de cámara fijos, y se compara con
+// su imagen de referencia en tests/golden/ con una tolerancia perceptual.
+//
+//   cargo test --test golden                     compara con las referencias
+//   UPDATE_GOLDENS=1 cargo test --test golden    vuelve a generar las referencias
+//
+// Si una imagen no coincide se escriben la imagen obtenida y un mapa de diferencias
+// (rojo = píxel distinto, sobre la referencia en gris) en target/tmp/golden-diff/.
+
+use std::path::{Path, PathBuf};
+
+use nalgebra_glm as glm;
+use raylib::prelude::*;
+
+use spacetravel::camera::Camera;
+use spacetravel::framebuffer::FrameBuffer;
+use spacetravel::mesh::Mesh;
+use spacetravel::profiler::Profiler;
+use spacetravel::registry::ShaderRegistry;
+use spacetravel::render::{RenderMode, ShadingMode, Surface, draw_mesh, draw_shell, project_mesh};
+use spacetravel::shaders::{
+    Biome, BodyShader, ProceduralParams, ShaderParams, SunLight, shade_body, shade_shell,
+    sun_direction_model,
+};
+
+const UPDATE_ENV: &str = "UPDATE_GOLDENS";
+const IMAGE_SIZE: i32 = 128;
+// Semilla de los planetas procedurales de prueba (uno por bioma)
+const PROCEDURAL_SEED: u32 = 7;
+
+// Un píxel cuenta como distinto si su ΔE (CIELAB) supera MAX_DELTA_E; la imagen falla
+// si los píxeles distintos superan MAX_BAD_FRACTION (bordes del contorno, redondeos
+// de sin/cos entre plataformas)
+const MAX_DELTA_E: f32 = 3.0;
+const MAX_BAD_FRACTION: f32 = 0.002;
+
+// Toma fija: cámara orbitando el cuerpo (grados), dirección del Sol y tiempo
+struct Shot {
+    name: &'static str,
+    azimuth: f32,
+    elevation: f32,
+    sun_dir: [f32; 3],
+    time: f32,
+}
+
+const SHOTS: [Shot; 2] = [
+    // De frente, con el Sol detrás de la cámara: lado diurno completo
+    Shot {
+        name: "dia",
+        azimuth: 0.0,
+        elevation: 10.0,
+        sun_dir: [0.3, 0.2, 1.0],
+        time: 0.0,
+    },
+    // Desde arriba a un costado, con el Sol de lado: terminador y lado nocturno
+    Shot {
+        name: "terminador",
+        azimuth: 35.0,
+        elevation: 30.0,
+        sun_dir: [-1.0, 0.1, 0.2],
+        time: 12.5,
+    },
+];
+
+// Todos los shaders del sistema por nombre + un planeta procedural por bioma
+fn bodies() -> Vec<(String, BodyShader)> {
+    let registry = ShaderRegistry::with_builtins();
+    let mut bodies: Vec<(String, BodyShader)> = registry
+        .names()
+        .map(|name| (name.to_string(), registry.get(name).expect("nombre del registro")))
+        .collect();
+    for biome in Biome::ALL {
+        let params = ProceduralParams::with_biome(PROCEDURAL_SEED, biome);
+        let name = format!("procedural-{}", biome.label().to_lowercase());
+        bodies.push((name, BodyShader::Procedural(params)));
+    }
+    bodies
+}
+
+fn manifest_path(rel: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(rel)
+}
+
+//
+// ----- RENDER SIN VENTANA -----
+//
+
+// Igual que el bucle principal para un cuerpo: superficie con Phong + contorno y
+// los cascos translúcidos encima. Sin mapa de sombras (visibilidad 1).
+fn render_body(
+    sphere: &Mesh,
+    kind: &BodyShader,
+    params: &ShaderParams,
+    shot: &Shot,
+) -> FrameBuffer {
+    let mut fb = FrameBuffer::new(IMAGE_SIZE, IMAGE_SIZE, Color::BLACK);
+    let mut prof = Profiler::new();
+    let center = sphere.center;
+    let radius = sphere.bounding_radius();
+
+    // Cuerpo en el origen; cámara a una distancia que deja un margen alrededor
+    let model = glm::translation(&(-center));
+    let body_world = glm::vec3(0.0, 0.0, 0.0);
+    let (az, el) = (shot.azimuth.to_radians(), shot.elevation.to_radians());
+    let eye_dir = glm::vec3(el.cos() * az.sin(), el.sin(), el.cos() * az.cos());
+    let cam_fov = Camera::default().fov_y;
+    let dist = radius * 1.35 / (cam_fov * 0.5).tan();
+    let forward = -eye_dir;
+    let cam = Camera {
+        pos: body_world + eye_dir * dist,
+        yaw: forward.z.atan2(forward.x),
+        pitch: forward.y.asin(),
+        ..Camera::default()
+    };
+    let view = cam.view_matrix();
+    let proj = cam.proj_matrix(1.0);
+
+    let sun_world = glm::normalize(&glm::Vec3::from(shot.sun_dir)) * (radius * 1000.0);
+    let sun_dir = sun_direction_model(&model, body_world, sun_world);
+    let sun = SunLight {
+        dir: sun_dir,
+        visibility: 1.0,
+    };
+
+    let mv = view * model;
+    let pm = project_mesh(sphere, &mv, &proj, &fb);
+    let surface = Surface::new(sphere, &mv, ShadingMode::Phong, |frag| {
+        shade_body(kind, params, frag, center, radius, shot.time, &sun)
+    });
+    draw_mesh(&mut fb, &pm, &surface, RenderMode::Shaded, 2, &mut prof);
+
+    let view3 = glm::mat4_to_mat3(&view);
+    let ray_view = glm::normalize(&(view3 * (body_world - cam.pos)));
+    let sun_view = glm::normalize(&(view3 * (sun_world - body_world)));
+    for layer in kind.shells(params) {
+        let k = layer.scale();
+        let shell_model = model
+            * glm::translation(&center)
+            * glm::scaling(&glm::vec3(k, k, k))
+            * glm::translation(&(-center));
+        let mv = view * shell_model;
+        let pm = project_mesh(sphere, &mv, &proj, &fb);
+        let shell_sun = SunLight {
+            dir: sun_direction_model(&shell_model, body_world, sun_world),
+            visibility: 1.0,
+        };
+        let surface = Surface::new(sphere, &mv, ShadingMode::Phong, |frag| {
+            shade_shell(&layer, frag, &shell_sun, ray_view, sun_view, shot.time)
+        });
+        draw_shell(&mut fb, &pm, &surface, &mut prof);
+    }
+    fb
+}
+
+//
+// ----- COMPARACIÓN PERCEPTUAL -----
+//
+
+// sRGB (0..255) -> CIELAB con blanco D65
+fn lab(c: [u8; 3]) -> [f32; 3] {
+    let lin = |v: u8| {
+        let v = v as f32 / 255.0;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (lin(c[0]), lin(c[1]), lin(c[2]));
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let f = |t: f32| {
+        if t > 0.008856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn delta_e(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (la, lb) = (lab(a), lab(b));
+    ((la[0] - lb[0]).powi(2) + (la[1] - lb[1]).powi(2) + (la[2] - lb[2]).powi(2)).sqrt()
+}
+
+// Compara `fb` con la referencia; Err con la descripción si no coinciden (y deja
+// la imagen obtenida y el mapa de diferencias en `diff_dir`)
+fn compare(fb: &FrameBuffer, golden: &Path, name: &str, diff_dir: &Path) -> Result<(), String> {
+    let path = golden.display();
+    let reference = Image::load_image(&golden.to_string_lossy())
+        .map_err(|e| format!("{path}: no se pudo leer ({e}); genérala con {UPDATE_ENV}=1"))?;
+    if reference.width() != fb.width || reference.height() != fb.height {
+        return Err(format!(
+            "{path}: tamaño {}x{}, se esperaba {}x{}",
+            reference.width(),
+            reference.height(),
+            fb.width,
+            fb.height
+        ));
+    }
+
+    let expected = reference.get_image_data();
+    let mut diff = FrameBuffer::new(fb.width, fb.height, Color::BLACK);
+    let mut bad = 0;
+    let mut worst: f32 = 0.0;
+    for (i, (px, exp)) in fb.pixels().chunks_exact(4).zip(expected.iter()).enumerate() {
+        let exp = [exp.r, exp.g, exp.b];
+        let de = delta_e([px[0], px[1], px[2]], exp);
+        worst = worst.max(de);
+        let color = if de > MAX_DELTA_E {
+            bad += 1;
+            Color::new(255, 0, 0, 255)
+        } else {
+            let gray = ((exp[0] as u32 + exp[1] as u32 + exp[2] as u32) / 9) as u8;
+            Color::new(gray, gray, gray, 255)
+        };
+        diff.set_color(color);
+        diff.set_pixel(i as i32 % fb.width, i as i32 / fb.width);
+    }
+
+    let fraction = bad as f32 / (fb.width * fb.height) as f32;
+    if fraction <= MAX_BAD_FRACTION {
+        return Ok(());
+    }
+    let actual_path = diff_dir.join(format!("{name}.png"));
+    let diff_path = diff_dir.join(format!("{name}.diff.png"));
+    fb.render_to_file(&actual_path.to_string_lossy())
+        .and_then(|_| diff.render_to_file(&diff_path.to_string_lossy()))
+        .map_err(|e| format!("{name}: error escribiendo diferencias: {e}"))?;
+    Err(format!(
+        "{name}: {:.2}% de píxeles distintos (máx. ΔE {worst:.1}); ver {}",
+        fraction * 100.0,
+        diff_path.display()
+    ))
+}
+
+#[test]
+fn body_shaders_match_golden_images() {
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    let golden_dir = manifest_path("tests/golden");
+    let diff_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden-diff");
+    std::fs::create_dir_all(if update { &golden_dir } else { &diff_dir })
+        .expect("no se pudo crear el directorio de imágenes");
+
+    let sphere = Mesh::load(&manifest_path("assets/sphere.obj").to_string_lossy())
+        .expect("no se pudo cargar assets/sphere.obj");
+    let params = ShaderParams::default();
+
+    let mut failures = Vec::new();
+    for (body, kind) in bodies() {
+        for shot in &SHOTS {
+            let name = format!("{body}-{}", shot.name);
+            let fb = render_body(&sphere, &kind, &params, shot);
+            let golden = golden_dir.join(format!("{name}.png"));
+            if update {
+                fb.render_to_file(&golden.to_string_lossy())
+                    .expect("no se pudo escribir la referencia");
+            } else if let Err(e) = compare(&fb, &golden, &name, &diff_dir) {
+                failures.push(e);
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} imágenes no coinciden con su referencia:\n  {}",
+        failures.len(),
+        failures.join("\n  ")
+    );
+}